@@ -0,0 +1,316 @@
+//! ASCII armor: a text-safe wrapper around the binary QSFS container,
+//! modeled on OpenPGP's armored blocks (base64 body + CRC-24 checksum
+//! between `-----BEGIN .../-----END ...` delimiters).
+
+use anyhow::{Context, Result, bail};
+use base64::{engine::general_purpose, Engine as _};
+
+const BEGIN_MESSAGE: &str = "-----BEGIN QSFS MESSAGE-----";
+const END_MESSAGE: &str = "-----END QSFS MESSAGE-----";
+/// Block markers for a standalone header (chunk3-3) — e.g. one pasted into
+/// a ticket or config file rather than shipped inside a binary container.
+const BEGIN_HEADER: &str = "-----BEGIN QSFS HEADER-----";
+const END_HEADER: &str = "-----END QSFS HEADER-----";
+/// Block markers for a detached signature set (the `SignatureMetadata`
+/// numbered header-lines from chunk3-2), armored on its own so it can be
+/// attached to a file it didn't travel inside.
+const BEGIN_SIGNATURE: &str = "-----BEGIN QSFS SIGNATURE-----";
+const END_SIGNATURE: &str = "-----END QSFS SIGNATURE-----";
+/// Block markers for a bare ML-DSA-87 public key (chunk4-3) — a
+/// copy-pasteable alternative to handing around `public_key_base64` with
+/// no framing or integrity check of its own.
+const BEGIN_MLDSA87_PUBLIC_KEY: &str = "-----BEGIN QSFS MLDSA87 PUBLIC KEY-----";
+const END_MLDSA87_PUBLIC_KEY: &str = "-----END QSFS MLDSA87 PUBLIC KEY-----";
+/// Block markers for a bare detached ML-DSA-87 signature (chunk4-3), as
+/// opposed to the full signer/algorithm/key `QSFS SIGNATURE` block above —
+/// this is just the raw signature bytes, for pasting next to a file whose
+/// signer is already known out of band.
+const BEGIN_MLDSA87_SIGNATURE: &str = "-----BEGIN QSFS MLDSA87 SIGNATURE-----";
+const END_MLDSA87_SIGNATURE: &str = "-----END QSFS MLDSA87 SIGNATURE-----";
+const LINE_WIDTH: usize = 64;
+
+/// CRC-24 as specified by OpenPGP (RFC 4880 §6.1).
+fn crc24(data: &[u8]) -> u32 {
+    const INIT: u32 = 0x00B7_04CE;
+    const POLY: u32 = 0x0186_4CFB;
+    let mut crc = INIT;
+    for &byte in data {
+        crc ^= (byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x0100_0000 != 0 {
+                crc ^= POLY;
+            }
+        }
+    }
+    crc & 0x00FF_FFFF
+}
+
+/// Wrap `data` between `begin`/`end` markers, with `headers` (e.g.
+/// `("suite", "aes256-gcm-siv")`) emitted as `key: value` lines before the
+/// blank line that precedes the base64 body, followed by a CRC-24
+/// checksum line prefixed with `=`.
+fn armor_block(data: &[u8], headers: &[(String, String)], begin: &str, end: &str) -> String {
+    let mut out = String::new();
+    out.push_str(begin);
+    out.push('\n');
+    for (k, v) in headers {
+        out.push_str(&format!("{}: {}\n", k, v));
+    }
+    out.push('\n');
+
+    let body = general_purpose::STANDARD.encode(data);
+    for chunk in body.as_bytes().chunks(LINE_WIDTH) {
+        out.push_str(std::str::from_utf8(chunk).expect("base64 is ASCII"));
+        out.push('\n');
+    }
+
+    let crc = crc24(data).to_be_bytes();
+    let crc_b64 = general_purpose::STANDARD.encode(&crc[1..4]); // low 24 bits
+    out.push('=');
+    out.push_str(&crc_b64);
+    out.push('\n');
+    out.push_str(end);
+    out.push('\n');
+    out
+}
+
+/// Recover the payload wrapped between `begin`/`end` markers, rejecting it
+/// if either marker is missing/out of order or the CRC-24 checksum line
+/// doesn't match — so a block of the wrong type or a corrupted one both
+/// fail before the bytes ever reach the caller's deserializer.
+fn dearmor_block(text: &str, begin: &str, end: &str) -> Result<Vec<u8>> {
+    let start = text.find(begin).ok_or_else(|| anyhow::anyhow!("missing BEGIN marker"))?;
+    let end_pos = text.find(end).ok_or_else(|| anyhow::anyhow!("missing END marker"))?;
+    if end_pos < start {
+        bail!("END marker precedes BEGIN marker");
+    }
+    let body_region = &text[start + begin.len()..end_pos];
+
+    let mut body_lines = Vec::new();
+    let mut crc_line: Option<&str> = None;
+    let mut past_headers = false;
+    // The first line is the (empty) remainder of the BEGIN marker's own line.
+    for line in body_region.lines().skip(1) {
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
+            past_headers = true;
+            continue;
+        }
+        if !past_headers {
+            // Armor header (`key: value`); skip.
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix('=') {
+            crc_line = Some(rest);
+            break;
+        }
+        body_lines.push(line);
+    }
+
+    let body_b64: String = body_lines.concat();
+    let data = general_purpose::STANDARD
+        .decode(body_b64)
+        .map_err(|e| anyhow::anyhow!("invalid armor body base64: {}", e))?;
+
+    let crc_line = crc_line.ok_or_else(|| anyhow::anyhow!("missing CRC-24 checksum line"))?;
+    let crc_bytes = general_purpose::STANDARD
+        .decode(crc_line)
+        .map_err(|e| anyhow::anyhow!("invalid CRC-24 base64: {}", e))?;
+    if crc_bytes.len() != 3 {
+        bail!("CRC-24 checksum must be 3 bytes, got {}", crc_bytes.len());
+    }
+    let expected = crc24(&data);
+    let got = u32::from_be_bytes([0, crc_bytes[0], crc_bytes[1], crc_bytes[2]]);
+    if expected != got {
+        bail!("CRC-24 mismatch: armor body is corrupt");
+    }
+
+    Ok(data)
+}
+
+/// Wrap `data` in an ASCII-armored `QSFS MESSAGE` block, with `headers`
+/// (e.g. `("suite", "aes256-gcm-siv")`) emitted as `key: value` lines
+/// before the blank line that precedes the body.
+pub fn armor(data: &[u8], headers: &[(String, String)]) -> String {
+    armor_block(data, headers, BEGIN_MESSAGE, END_MESSAGE)
+}
+
+/// True if `bytes` looks like an armored block rather than the raw binary
+/// container (whose first four bytes are a big-endian header length).
+pub fn is_armored(bytes: &[u8]) -> bool {
+    bytes.starts_with(BEGIN_MESSAGE.as_bytes())
+}
+
+/// Recover the original binary payload from an armored `QSFS MESSAGE`
+/// block, rejecting it if the CRC-24 checksum line doesn't match.
+pub fn dearmor(text: &str) -> Result<Vec<u8>> {
+    dearmor_block(text, BEGIN_MESSAGE, END_MESSAGE)
+}
+
+/// Wrap a serialized `Header` (postcard bytes) in a standalone
+/// `QSFS HEADER` armored block, so it can be pasted into a ticket, email,
+/// or config file independent of the container it was cut from.
+pub fn armor_header(header_bytes: &[u8]) -> String {
+    armor_block(header_bytes, &[], BEGIN_HEADER, END_HEADER)
+}
+
+/// True if `bytes` looks like a standalone armored header block.
+pub fn is_armored_header(bytes: &[u8]) -> bool {
+    bytes.starts_with(BEGIN_HEADER.as_bytes())
+}
+
+/// Recover the postcard header bytes from a `QSFS HEADER` armored block,
+/// validating the CRC-24 checksum before handing them to the existing
+/// `postcard` header deserialization path.
+pub fn parse_armored_header(text: &str) -> Result<Vec<u8>> {
+    dearmor_block(text, BEGIN_HEADER, END_HEADER)
+}
+
+/// Armor a detached signature set (chunk3-2) as a `QSFS SIGNATURE` block:
+/// the body is the numbered header-line text
+/// `canonical::SignatureMetadata::to_header_lines` produces, so the
+/// signatures can travel separately from the file/header they cover.
+pub fn armor_signature_metadata(entries: &[crate::canonical::SignatureMetadata]) -> String {
+    let body = crate::canonical::SignatureMetadata::to_header_lines(entries).join("\n");
+    armor_block(body.as_bytes(), &[], BEGIN_SIGNATURE, END_SIGNATURE)
+}
+
+/// True if `bytes` looks like a standalone armored signature block.
+pub fn is_armored_signature(bytes: &[u8]) -> bool {
+    bytes.starts_with(BEGIN_SIGNATURE.as_bytes())
+}
+
+/// Recover the signature set from a `QSFS SIGNATURE` armored block,
+/// validating the CRC-24 checksum before handing the recovered text to
+/// `canonical::SignatureMetadata::from_header_lines`.
+pub fn parse_armored_signature(text: &str) -> Result<Vec<crate::canonical::SignatureMetadata>> {
+    let body = dearmor_block(text, BEGIN_SIGNATURE, END_SIGNATURE)?;
+    let body = String::from_utf8(body).context("armored signature body is not valid UTF-8")?;
+    let lines: Vec<String> = body.lines().map(|l| l.to_string()).collect();
+    crate::canonical::SignatureMetadata::from_header_lines(&lines)
+}
+
+/// Armor a bare ML-DSA-87 public key (chunk4-3), e.g. for
+/// `Signer::export_public_armored`.
+pub fn armor_public_key(pk_bytes: &[u8]) -> String {
+    armor_block(pk_bytes, &[], BEGIN_MLDSA87_PUBLIC_KEY, END_MLDSA87_PUBLIC_KEY)
+}
+
+/// True if `bytes` looks like an armored ML-DSA-87 public key block.
+pub fn is_armored_public_key(bytes: &[u8]) -> bool {
+    bytes.starts_with(BEGIN_MLDSA87_PUBLIC_KEY.as_bytes())
+}
+
+/// Recover the raw public key bytes from an armored block produced by
+/// [`armor_public_key`], validating the CRC-24 checksum first.
+pub fn import_public_armored(text: &str) -> Result<Vec<u8>> {
+    dearmor_block(text, BEGIN_MLDSA87_PUBLIC_KEY, END_MLDSA87_PUBLIC_KEY)
+}
+
+/// Armor a bare detached ML-DSA-87 signature (chunk4-3).
+pub fn armor_signature(sig: &[u8]) -> String {
+    armor_block(sig, &[], BEGIN_MLDSA87_SIGNATURE, END_MLDSA87_SIGNATURE)
+}
+
+/// True if `bytes` looks like an armored bare-signature block.
+pub fn is_armored_mldsa87_signature(bytes: &[u8]) -> bool {
+    bytes.starts_with(BEGIN_MLDSA87_SIGNATURE.as_bytes())
+}
+
+/// Recover the raw signature bytes from an armored block produced by
+/// [`armor_signature`], validating the CRC-24 checksum first.
+pub fn dearmor_signature(text: &str) -> Result<Vec<u8>> {
+    dearmor_block(text, BEGIN_MLDSA87_SIGNATURE, END_MLDSA87_SIGNATURE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_arbitrary_bytes() {
+        let data = b"hello qsfs armor\x00\x01\xff".to_vec();
+        let armored = armor(&data, &[("suite".to_string(), "aes256-gcm-siv".to_string())]);
+        assert!(is_armored(armored.as_bytes()));
+        let recovered = dearmor(&armored).unwrap();
+        assert_eq!(recovered, data);
+    }
+
+    #[test]
+    fn rejects_tampered_body() {
+        let data = b"tamper me".to_vec();
+        let armored = armor(&data, &[]);
+        // Flip one base64 body character without touching the checksum line.
+        let mut bytes = armored.into_bytes();
+        let body_start = bytes.iter().position(|&b| b == b'\n').unwrap() + 2;
+        bytes[body_start] = if bytes[body_start] == b'A' { b'B' } else { b'A' };
+        let tampered = String::from_utf8(bytes).unwrap();
+        assert!(dearmor(&tampered).is_err());
+    }
+
+    #[test]
+    fn crc24_matches_known_vector() {
+        // CRC-24 of the empty string is the untouched initialization value.
+        assert_eq!(crc24(b""), 0x00B7_04CE);
+    }
+
+    #[test]
+    fn round_trips_a_standalone_header() {
+        let header_bytes = b"postcard-encoded-header-bytes".to_vec();
+        let armored = armor_header(&header_bytes);
+        assert!(is_armored_header(armored.as_bytes()));
+        assert!(!is_armored(armored.as_bytes()), "header block must not look like a message block");
+        assert_eq!(parse_armored_header(&armored).unwrap(), header_bytes);
+    }
+
+    #[test]
+    fn header_armor_rejects_message_block_markers() {
+        let armored = armor(b"not a header", &[]);
+        assert!(parse_armored_header(&armored).is_err());
+    }
+
+    #[test]
+    fn round_trips_a_detached_signature_set() {
+        use crate::canonical::SignatureMetadata;
+
+        let entries = vec![
+            SignatureMetadata::new(
+                "author".to_string(),
+                "ml-dsa-87+ed25519".to_string(),
+                vec![1, 2, 3, 4],
+                vec![5, 6, 7, 8],
+                vec![9; 8],
+                vec![10; 8],
+            ),
+        ];
+        let armored = armor_signature_metadata(&entries);
+        assert!(is_armored_signature(armored.as_bytes()));
+        let recovered = parse_armored_signature(&armored).unwrap();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].signer_id, "author");
+        assert_eq!(recovered[0].public_key_bytes().unwrap(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn round_trips_an_armored_public_key() {
+        let pk = vec![7u8; 2592];
+        let armored = armor_public_key(&pk);
+        assert!(is_armored_public_key(armored.as_bytes()));
+        assert_eq!(import_public_armored(&armored).unwrap(), pk);
+    }
+
+    #[test]
+    fn round_trips_an_armored_signature() {
+        let sig = vec![9u8; 4595];
+        let armored = armor_signature(&sig);
+        assert!(is_armored_mldsa87_signature(armored.as_bytes()));
+        assert_eq!(dearmor_signature(&armored).unwrap(), sig);
+    }
+
+    #[test]
+    fn public_key_armor_rejects_signature_block_markers() {
+        let armored = armor_signature(b"not a key");
+        assert!(import_public_armored(&armored).is_err());
+    }
+}
@@ -1,6 +1,8 @@
-use anyhow::Result;
-use crate::header::Header;
+use anyhow::{Context, Result};
+use crate::header::{Header, ManifestEntry, PassphraseRecipient, RecipientEntry};
+use crate::suite::SuiteId;
 use base64::{engine::general_purpose, Engine as _};
+use std::collections::HashMap;
 
 /// Canonical header serialization for signing
 /// 
@@ -17,18 +19,20 @@ impl CanonicalHeader {
         // Header format version (QSFS v2)
         canonical.extend_from_slice(b"qsfs/v2\n");
         
-        // Parameters line
-        canonical.extend_from_slice(b"params: aesgcm256 mlkem1024\n");
-        
+        // Parameters line: the full negotiated (KEM, KDF, AEAD) triple, so a
+        // signature binds every component and substituting any one of them
+        // fails verification rather than just failing decryption.
+        canonical.extend_from_slice(format!("params: {}\n", header.suite.full_descriptor()).as_bytes());
+
         // Chunk size
         canonical.extend_from_slice(format!("chunk: {}\n", header.chunk_size).as_bytes());
-        
+
         // Context (file ID as base64)
         let context_b64 = general_purpose::STANDARD.encode(header.file_id);
         canonical.extend_from_slice(format!("context: {}\n", context_b64).as_bytes());
-        
+
         // AEAD algorithm
-        canonical.extend_from_slice(b"aead: aes256gcm-v2\n");
+        canonical.extend_from_slice(format!("aead: {}\n", header.suite.aead().as_str()).as_bytes());
         
         // Recipients (deterministic ordering by ML-KEM ct bytes)
         let mut recipients = header.recipients.clone();
@@ -40,6 +44,7 @@ impl CanonicalHeader {
             let wrapped_b64 = general_purpose::STANDARD.encode(&r.wrapped_dek);
             let nonce_b64 = general_purpose::STANDARD.encode(r.wrap_nonce);
             let xpub_b64 = general_purpose::STANDARD.encode(&r.x25519_pub);
+            let enc_b64 = general_purpose::STANDARD.encode(&r.enc);
             let xfpr_hex = {
                 let mut s = String::with_capacity(16);
                 for b in &r.x25519_pk_fpr { s.push_str(&format!("{:02x}", b)); }
@@ -49,12 +54,46 @@ impl CanonicalHeader {
             // Include both legacy wrap and new AEAD wrap fields under clear keys
             canonical.extend_from_slice(
                 format!(
-                    "recip: label={} ct={} wrap_legacy={} gcm_nonce={} gcm_wrap={} x25519_pk={} x25519_fpr={}\n",
-                    r.label, ct_b64, wrap_legacy_b64, nonce_b64, wrapped_b64, xpub_b64, xfpr_hex
+                    "recip: label={} ct={} wrap_legacy={} gcm_nonce={} gcm_wrap={} x25519_pk={} x25519_fpr={} enc={}\n",
+                    r.label, ct_b64, wrap_legacy_b64, nonce_b64, wrapped_b64, xpub_b64, xfpr_hex, enc_b64
                 ).as_bytes()
             );
         }
         
+        // Passphrase recipients (chunk2-4), sorted by label for the same
+        // order-independence reason as the KEM recipients above.
+        let mut passphrase_recipients = header.passphrase_recipients.clone();
+        passphrase_recipients.sort_by(|a, b| a.label.cmp(&b.label));
+
+        for p in &passphrase_recipients {
+            let wrapped_b64 = general_purpose::STANDARD.encode(&p.wrapped_dek);
+            let nonce_b64 = general_purpose::STANDARD.encode(p.wrap_nonce);
+            canonical.extend_from_slice(
+                format!(
+                    "passrecip: label={} wrap={} nonce={} mem_kib={} time_cost={} parallelism={}\n",
+                    p.label, wrapped_b64, nonce_b64, p.argon2_mem_kib, p.argon2_time_cost, p.argon2_parallelism
+                ).as_bytes()
+            );
+        }
+
+        // Bundle manifest (chunk1-7), sorted by relative_path so signing
+        // doesn't depend on the order files were added to the bundle.
+        if let Some(manifest) = &header.manifest {
+            let mut entries = manifest.clone();
+            entries.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+            for e in &entries {
+                let digest_hex = {
+                    let mut s = String::with_capacity(64);
+                    for b in &e.blake3_digest { s.push_str(&format!("{:02x}", b)); }
+                    s
+                };
+                canonical.extend_from_slice(
+                    format!("manifest: path={} len={} digest={}\n", e.relative_path, e.length, digest_hex).as_bytes()
+                );
+            }
+        }
+
         // Reserved hash field (deprecated)
         let hash_b64 = general_purpose::STANDARD.encode(header.blake3_of_plain);
         canonical.extend_from_slice(format!("hash_resvd: {}\n", hash_b64).as_bytes());
@@ -81,78 +120,344 @@ impl CanonicalHeader {
         let canonical = Self::serialize(header)?;
         Ok(String::from_utf8(canonical)?)
     }
+
+    /// Inverse of [`Self::serialize`]: reconstruct a `Header` from its
+    /// canonical bytes, so a verifier that only received a signed header
+    /// as text (e.g. over `cose::verify` or a detached signature) can
+    /// recompute `serialize` itself rather than needing a separately
+    /// transmitted binary `Header` it's forced to trust unverified.
+    ///
+    /// `serialize` deliberately excludes some `Header` fields — `magic`,
+    /// `kdf_salt`, `compression`, the signature fields themselves (see
+    /// `serialize`'s doc comment for why) — so they don't round-trip;
+    /// `parse` fills them with the format's defaults (`magic = *b"QSFS2\0"`,
+    /// `kdf_salt = None`, `compression = None`, empty/absent signatures).
+    /// Since none of those fields affect `serialize`'s output,
+    /// `serialize(&parse(bytes)?) == bytes` still holds.
+    pub fn parse(bytes: &[u8]) -> Result<Header> {
+        let text = std::str::from_utf8(bytes).context("canonical header is not valid UTF-8")?;
+        let mut lines = text.lines();
+
+        let version_line = lines.next().context("empty canonical header")?;
+        if version_line != "qsfs/v2" {
+            anyhow::bail!("unrecognized canonical header version line: {}", version_line);
+        }
+
+        let mut suite: Option<SuiteId> = None;
+        let mut chunk_size: Option<u32> = None;
+        let mut file_id: Option<[u8; 8]> = None;
+        let mut recipients = Vec::new();
+        let mut passphrase_recipients = Vec::new();
+        let mut manifest_entries = Vec::new();
+        let mut has_manifest = false;
+        let mut blake3_of_plain: Option<[u8; 32]> = None;
+        let mut eph_x25519_pk: Option<[u8; 32]> = None;
+        let mut fin: Option<u8> = None;
+
+        for line in lines {
+            let Some((key, rest)) = line.split_once(": ") else { continue };
+            match key {
+                "params" => {
+                    suite = Some(SuiteId::from_full_descriptor(rest).map_err(|e| anyhow::anyhow!(e))?);
+                }
+                "chunk" => {
+                    chunk_size = Some(rest.parse().context("invalid chunk size in canonical header")?);
+                }
+                "context" => {
+                    file_id = Some(decode_fixed_b64(rest, "context")?);
+                }
+                "aead" => {
+                    let suite = suite.context("aead line appeared before params line")?;
+                    if suite.aead().as_str() != rest {
+                        anyhow::bail!("aead line '{}' disagrees with suite '{}'", rest, suite.aead().as_str());
+                    }
+                }
+                "recip" => recipients.push(parse_recipient_line(rest)?),
+                "passrecip" => passphrase_recipients.push(parse_passphrase_line(rest)?),
+                "manifest" => {
+                    has_manifest = true;
+                    manifest_entries.push(parse_manifest_line(rest)?);
+                }
+                "hash_resvd" => blake3_of_plain = Some(decode_fixed_b64(rest, "hash_resvd")?),
+                "ephx25519" => eph_x25519_pk = Some(decode_fixed_b64(rest, "ephx25519")?),
+                "fin" => fin = Some(rest.parse().context("invalid fin marker")?),
+                _ => {}
+            }
+        }
+
+        Ok(Header {
+            magic: *b"QSFS2\0",
+            chunk_size: chunk_size.context("canonical header missing 'chunk' line")?,
+            file_id: file_id.context("canonical header missing 'context' line")?,
+            blake3_of_plain: blake3_of_plain.context("canonical header missing 'hash_resvd' line")?,
+            suite: suite.context("canonical header missing 'params' line")?,
+            kdf_salt: None,
+            compression: None,
+            recipients,
+            passphrase_recipients,
+            eph_x25519_pk: eph_x25519_pk.context("canonical header missing 'ephx25519' line")?,
+            mldsa_sig: Vec::new(),
+            ed25519_sig: Vec::new(),
+            signature_metadata: None,
+            co_signatures: Vec::new(),
+            manifest: if has_manifest { Some(manifest_entries) } else { None },
+            fin: fin.context("canonical header missing 'fin' line")?,
+        })
+    }
+}
+
+/// Split a `key=value key=value ...` line body on whitespace (safe since
+/// none of base64, hex, or the label/numeric fields it separates contain
+/// spaces), then each token on its first `=` (safe since keys never
+/// contain `=`, only base64 values' trailing padding does).
+fn parse_field_pairs(rest: &str) -> HashMap<&str, &str> {
+    rest.split_whitespace().filter_map(|tok| tok.split_once('=')).collect()
+}
+
+fn field<'a>(fields: &HashMap<&str, &'a str>, key: &str) -> Result<&'a str> {
+    fields.get(key).copied().with_context(|| format!("line missing '{}' field", key))
+}
+
+fn decode_b64(value: &str) -> Result<Vec<u8>> {
+    general_purpose::STANDARD.decode(value).context("invalid base64 field")
 }
 
-/// Signature metadata for header
+fn decode_fixed_b64<const N: usize>(value: &str, field_name: &str) -> Result<[u8; N]> {
+    let bytes = decode_b64(value)?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("'{}' field must decode to {} bytes", field_name, N))
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>> {
+    if value.len() % 2 != 0 {
+        anyhow::bail!("hex field has odd length");
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&value[i..i + 2], 16).map_err(|e| anyhow::anyhow!("invalid hex digit: {}", e)))
+        .collect()
+}
+
+fn decode_fixed_hex<const N: usize>(value: &str, field_name: &str) -> Result<[u8; N]> {
+    let bytes = decode_hex(value)?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("'{}' field must decode to {} bytes", field_name, N))
+}
+
+fn parse_recipient_line(rest: &str) -> Result<RecipientEntry> {
+    let fields = parse_field_pairs(rest);
+    Ok(RecipientEntry {
+        label: field(&fields, "label")?.to_string(),
+        mlkem_ct: decode_b64(field(&fields, "ct")?)?,
+        wrap: decode_b64(field(&fields, "wrap_legacy")?)?,
+        wrapped_dek: decode_b64(field(&fields, "gcm_wrap")?)?,
+        wrap_nonce: decode_fixed_b64(field(&fields, "gcm_nonce")?, "gcm_nonce")?,
+        x25519_pk_fpr: decode_fixed_hex(field(&fields, "x25519_fpr")?, "x25519_fpr")?,
+        x25519_pub: decode_b64(field(&fields, "x25519_pk")?)?,
+        enc: decode_b64(field(&fields, "enc")?)?,
+    })
+}
+
+fn parse_passphrase_line(rest: &str) -> Result<PassphraseRecipient> {
+    let fields = parse_field_pairs(rest);
+    Ok(PassphraseRecipient {
+        label: field(&fields, "label")?.to_string(),
+        wrapped_dek: decode_b64(field(&fields, "wrap")?)?,
+        wrap_nonce: decode_fixed_b64(field(&fields, "nonce")?, "nonce")?,
+        argon2_mem_kib: field(&fields, "mem_kib")?.parse().context("invalid mem_kib")?,
+        argon2_time_cost: field(&fields, "time_cost")?.parse().context("invalid time_cost")?,
+        argon2_parallelism: field(&fields, "parallelism")?.parse().context("invalid parallelism")?,
+    })
+}
+
+fn parse_manifest_line(rest: &str) -> Result<ManifestEntry> {
+    let fields = parse_field_pairs(rest);
+    Ok(ManifestEntry {
+        relative_path: field(&fields, "path")?.to_string(),
+        length: field(&fields, "len")?.parse().context("invalid manifest length")?,
+        blake3_digest: decode_fixed_hex(field(&fields, "digest")?, "digest")?,
+    })
+}
+
+/// Signature metadata for header, in the text-friendly (base64) form used
+/// by armored/detached signature representations. Carries both the
+/// ML-DSA-87 and Ed25519 halves of a hybrid signature.
+///
+/// A header can carry more than one of these (chunk3-2) — e.g. an author
+/// signature plus a later counter-signature from an approver — so they
+/// travel as an ordered `Vec<SignatureMetadata>` rather than a single
+/// value; see `to_header_lines`/`from_header_lines` for how a set of them
+/// round-trips through numbered text blocks, and `verify_all`/
+/// `distinct_signer_count` for checking the set as a whole.
 #[derive(Debug, Clone)]
 pub struct SignatureMetadata {
     pub signer_id: String,
     pub algorithm: String,
-    pub public_key: String, // base64 encoded
-    pub signature: String,  // base64 encoded
+    pub public_key: String,         // base64 encoded ML-DSA-87 public key
+    pub ed25519_public_key: String, // base64 encoded Ed25519 public key
+    pub signature: String,          // base64 encoded ML-DSA-87 signature
+    pub ed25519_signature: String,  // base64 encoded Ed25519 signature
 }
 
 impl SignatureMetadata {
-    /// Create new signature metadata
-    pub fn new(signer_id: String, public_key: Vec<u8>, signature: Vec<u8>) -> Self {
+    /// Create new signature metadata. `algorithm` is reported by the
+    /// signer that produced `signature` (see `crate::signer::Signer`,
+    /// chunk3-1) rather than assumed, so this isn't limited to the hybrid
+    /// ML-DSA-87 + Ed25519 case.
+    pub fn new(
+        signer_id: String,
+        algorithm: String,
+        public_key: Vec<u8>,
+        ed25519_public_key: Vec<u8>,
+        signature: Vec<u8>,
+        ed25519_signature: Vec<u8>,
+    ) -> Self {
         SignatureMetadata {
             signer_id,
-            algorithm: "ml-dsa-87".to_string(),
+            algorithm,
             public_key: general_purpose::STANDARD.encode(public_key),
+            ed25519_public_key: general_purpose::STANDARD.encode(ed25519_public_key),
             signature: general_purpose::STANDARD.encode(signature),
+            ed25519_signature: general_purpose::STANDARD.encode(ed25519_signature),
         }
     }
-    
-    /// Serialize signature metadata to header lines
-    pub fn to_header_lines(&self) -> Vec<String> {
-        vec![
-            format!("signer: {}", self.signer_id),
-            format!("sigalg: {}", self.algorithm),
-            format!("sigpub: {}", self.public_key),
-            format!("sig: {}", self.signature),
-        ]
+
+    /// Serialize an ordered set of signatures to numbered header-line
+    /// blocks (`signer.0:`, `sigalg.0:`, …, `signer.1:`, …), OpenPGP-style.
+    /// `entries` is sorted by public-key bytes first, so the same signer
+    /// set always produces the same text regardless of collection order —
+    /// mirroring the recipient sort in `CanonicalHeader::serialize`.
+    pub fn to_header_lines(entries: &[SignatureMetadata]) -> Vec<String> {
+        let mut sorted: Vec<&SignatureMetadata> = entries.iter().collect();
+        sorted.sort_by_key(|e| (e.public_key_bytes().unwrap_or_default(), e.public_key.clone()));
+
+        let mut out = Vec::new();
+        for (i, entry) in sorted.into_iter().enumerate() {
+            out.push(format!("signer.{}: {}", i, entry.signer_id));
+            out.push(format!("sigalg.{}: {}", i, entry.algorithm));
+            out.push(format!("sigpub.{}: {}", i, entry.public_key));
+            out.push(format!("sig.{}: {}", i, entry.signature));
+            out.push(format!("edpub.{}: {}", i, entry.ed25519_public_key));
+            out.push(format!("edsig.{}: {}", i, entry.ed25519_signature));
+        }
+        out
     }
-    
-    /// Parse signature metadata from header lines
-    pub fn from_header_lines(lines: &[String]) -> Result<Self> {
-        let mut signer_id = None;
-        let mut algorithm = None;
-        let mut public_key = None;
-        let mut signature = None;
-        
+
+    /// Parse the numbered blocks `to_header_lines` produces back into an
+    /// ordered set of signatures, one per distinct index seen.
+    pub fn from_header_lines(lines: &[String]) -> Result<Vec<SignatureMetadata>> {
+        #[derive(Default)]
+        struct Partial {
+            signer_id: Option<String>,
+            algorithm: Option<String>,
+            public_key: Option<String>,
+            signature: Option<String>,
+            ed25519_public_key: Option<String>,
+            ed25519_signature: Option<String>,
+        }
+
+        let mut by_index: std::collections::BTreeMap<usize, Partial> = std::collections::BTreeMap::new();
+
         for line in lines {
-            if let Some(value) = line.strip_prefix("signer: ") {
-                signer_id = Some(value.to_string());
-            } else if let Some(value) = line.strip_prefix("sigalg: ") {
-                algorithm = Some(value.to_string());
-            } else if let Some(value) = line.strip_prefix("sigpub: ") {
-                public_key = Some(value.to_string());
-            } else if let Some(value) = line.strip_prefix("sig: ") {
-                signature = Some(value.to_string());
+            let Some((key, value)) = line.split_once(": ") else { continue };
+            let Some((field, index)) = key.rsplit_once('.') else { continue };
+            let Ok(index) = index.parse::<usize>() else { continue };
+            let entry = by_index.entry(index).or_default();
+            match field {
+                "signer" => entry.signer_id = Some(value.to_string()),
+                "sigalg" => entry.algorithm = Some(value.to_string()),
+                "sigpub" => entry.public_key = Some(value.to_string()),
+                "sig" => entry.signature = Some(value.to_string()),
+                "edpub" => entry.ed25519_public_key = Some(value.to_string()),
+                "edsig" => entry.ed25519_signature = Some(value.to_string()),
+                _ => {}
             }
         }
-        
-        Ok(SignatureMetadata {
-            signer_id: signer_id.ok_or_else(|| anyhow::anyhow!("Missing signer field"))?,
-            algorithm: algorithm.ok_or_else(|| anyhow::anyhow!("Missing sigalg field"))?,
-            public_key: public_key.ok_or_else(|| anyhow::anyhow!("Missing sigpub field"))?,
-            signature: signature.ok_or_else(|| anyhow::anyhow!("Missing sig field"))?,
-        })
+
+        by_index
+            .into_values()
+            .map(|p| {
+                Ok(SignatureMetadata {
+                    signer_id: p.signer_id.ok_or_else(|| anyhow::anyhow!("Missing signer field"))?,
+                    algorithm: p.algorithm.ok_or_else(|| anyhow::anyhow!("Missing sigalg field"))?,
+                    public_key: p.public_key.ok_or_else(|| anyhow::anyhow!("Missing sigpub field"))?,
+                    signature: p.signature.ok_or_else(|| anyhow::anyhow!("Missing sig field"))?,
+                    ed25519_public_key: p
+                        .ed25519_public_key
+                        .ok_or_else(|| anyhow::anyhow!("Missing edpub field"))?,
+                    ed25519_signature: p
+                        .ed25519_signature
+                        .ok_or_else(|| anyhow::anyhow!("Missing edsig field"))?,
+                })
+            })
+            .collect()
     }
-    
-    /// Get public key as bytes
+
+    /// True only if every entry in `entries` validates over
+    /// `canonical_bytes` (typically `CanonicalHeader::serialize`'s
+    /// output) — a single bad signature fails the whole set, same as a
+    /// corrupt OpenPGP signature packet fails the message. An empty set
+    /// is considered unverified (`Ok(false)`): callers that require at
+    /// least one signature don't need a separate empty check.
+    pub fn verify_all(entries: &[SignatureMetadata], canonical_bytes: &[u8]) -> Result<bool> {
+        if entries.is_empty() {
+            return Ok(false);
+        }
+        for entry in entries {
+            let ok = crate::signer::verify_hybrid_signature(
+                canonical_bytes,
+                &entry.signature_bytes()?,
+                &entry.public_key_bytes()?,
+                &entry.ed25519_signature_bytes()?,
+                &entry.ed25519_public_key_bytes()?,
+            )?;
+            if !ok {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Policy knob for "require N distinct signers": the number of unique
+    /// `signer_id`s present in `entries` (two entries from the same
+    /// signer — e.g. a resubmitted signature — count once).
+    pub fn distinct_signer_count(entries: &[SignatureMetadata]) -> usize {
+        entries
+            .iter()
+            .map(|e| e.signer_id.as_str())
+            .collect::<std::collections::HashSet<_>>()
+            .len()
+    }
+
+    /// Get ML-DSA-87 public key as bytes
     pub fn public_key_bytes(&self) -> Result<Vec<u8>> {
         general_purpose::STANDARD
             .decode(&self.public_key)
             .map_err(|e| anyhow::anyhow!("Invalid public key base64: {}", e))
     }
-    
-    /// Get signature as bytes
+
+    /// Get ML-DSA-87 signature as bytes
     pub fn signature_bytes(&self) -> Result<Vec<u8>> {
         general_purpose::STANDARD
             .decode(&self.signature)
             .map_err(|e| anyhow::anyhow!("Invalid signature base64: {}", e))
     }
+
+    /// Get Ed25519 public key as bytes
+    pub fn ed25519_public_key_bytes(&self) -> Result<Vec<u8>> {
+        general_purpose::STANDARD
+            .decode(&self.ed25519_public_key)
+            .map_err(|e| anyhow::anyhow!("Invalid Ed25519 public key base64: {}", e))
+    }
+
+    /// Get Ed25519 signature as bytes
+    pub fn ed25519_signature_bytes(&self) -> Result<Vec<u8>> {
+        general_purpose::STANDARD
+            .decode(&self.ed25519_signature)
+            .map_err(|e| anyhow::anyhow!("Invalid Ed25519 signature base64: {}", e))
+    }
 }
 
 #[cfg(test)]
@@ -168,6 +473,7 @@ mod tests {
             blake3_of_plain: [0u8; 32],
             suite: SuiteId::current(),
             kdf_salt: None,
+            compression: None,
             recipients: vec![
                 RecipientEntry {
                     label: "alice".to_string(),
@@ -177,6 +483,7 @@ mod tests {
                     wrap_nonce: [0u8;12],
                     x25519_pk_fpr: [0u8;8],
                     x25519_pub: vec![],
+                    enc: vec![1, 2, 3, 4],
                 },
                 RecipientEntry {
                     label: "bob".to_string(),
@@ -186,16 +493,20 @@ mod tests {
                     wrap_nonce: [0u8;12],
                     x25519_pk_fpr: [0u8;8],
                     x25519_pub: vec![],
+                    enc: vec![9, 10, 11, 12],
                 },
             ],
+            passphrase_recipients: vec![],
             eph_x25519_pk: [0u8;32],
             mldsa_sig: vec![],
             ed25519_sig: vec![],
             signature_metadata: None,
+            co_signatures: Vec::new(),
+            manifest: None,
             fin: 1,
         }
     }
-    
+
     #[test]
     fn test_canonical_serialization() {
         let header = create_test_header();
@@ -206,9 +517,9 @@ mod tests {
         
         // Should contain expected fields
         assert!(canonical_str.contains("qsfs/v2"));
-        assert!(canonical_str.contains("params: aesgcm256 mlkem1024"));
+        assert!(canonical_str.contains("params: mlkem1024x25519/hkdf-sha3-384/aes256-gcm-siv"));
         assert!(canonical_str.contains("chunk: 1048576"));
-        assert!(canonical_str.contains("aead: aes256gcm-v2"));
+        assert!(canonical_str.contains("aead: aes256-gcm-siv"));
         assert!(canonical_str.contains("recip: label=alice"));
         assert!(canonical_str.contains("recip: label=bob"));
         assert!(canonical_str.contains("ephx25519:"));
@@ -236,4 +547,135 @@ mod tests {
         
         assert_eq!(canonical1, canonical2);
     }
+
+    #[test]
+    fn parse_inverts_serialize() {
+        let mut header = create_test_header();
+        header.manifest = Some(vec![
+            crate::header::ManifestEntry { relative_path: "a.txt".to_string(), length: 1, blake3_digest: [1u8; 32] },
+            crate::header::ManifestEntry { relative_path: "b.txt".to_string(), length: 2, blake3_digest: [2u8; 32] },
+        ]);
+        header.passphrase_recipients = vec![crate::header::PassphraseRecipient {
+            label: "alice".to_string(),
+            wrapped_dek: vec![1, 1, 1],
+            wrap_nonce: [1u8; 12],
+            argon2_mem_kib: 262144,
+            argon2_time_cost: 3,
+            argon2_parallelism: 1,
+        }];
+
+        let canonical = CanonicalHeader::serialize(&header).unwrap();
+        let parsed = CanonicalHeader::parse(&canonical).unwrap();
+        let reserialized = CanonicalHeader::serialize(&parsed).unwrap();
+        assert_eq!(canonical, reserialized);
+    }
+
+    #[test]
+    fn test_manifest_is_covered_and_order_independent() {
+        use crate::header::ManifestEntry;
+
+        let mut header = create_test_header();
+        let without_manifest = CanonicalHeader::serialize(&header).unwrap();
+
+        header.manifest = Some(vec![
+            ManifestEntry { relative_path: "b.txt".to_string(), length: 2, blake3_digest: [2u8; 32] },
+            ManifestEntry { relative_path: "a.txt".to_string(), length: 1, blake3_digest: [1u8; 32] },
+        ]);
+        let with_manifest = CanonicalHeader::serialize(&header).unwrap();
+        assert_ne!(with_manifest, without_manifest);
+
+        header.manifest.as_mut().unwrap().reverse();
+        let with_manifest_reversed = CanonicalHeader::serialize(&header).unwrap();
+        assert_eq!(with_manifest, with_manifest_reversed, "manifest entries must sort deterministically");
+    }
+
+    #[test]
+    fn test_passphrase_recipients_are_covered_and_order_independent() {
+        use crate::header::PassphraseRecipient;
+
+        let mut header = create_test_header();
+        let without_passphrases = CanonicalHeader::serialize(&header).unwrap();
+
+        header.passphrase_recipients = vec![
+            PassphraseRecipient {
+                label: "bob".to_string(),
+                wrapped_dek: vec![2, 2, 2],
+                wrap_nonce: [2u8; 12],
+                argon2_mem_kib: 65536,
+                argon2_time_cost: 2,
+                argon2_parallelism: 1,
+            },
+            PassphraseRecipient {
+                label: "alice".to_string(),
+                wrapped_dek: vec![1, 1, 1],
+                wrap_nonce: [1u8; 12],
+                argon2_mem_kib: 262144,
+                argon2_time_cost: 3,
+                argon2_parallelism: 1,
+            },
+        ];
+        let with_passphrases = CanonicalHeader::serialize(&header).unwrap();
+        assert_ne!(with_passphrases, without_passphrases);
+
+        header.passphrase_recipients.reverse();
+        let with_passphrases_reversed = CanonicalHeader::serialize(&header).unwrap();
+        assert_eq!(with_passphrases, with_passphrases_reversed, "passphrase recipients must sort deterministically");
+    }
+
+    fn signature_metadata_entry(signer_id: &str, pubkey_byte: u8) -> SignatureMetadata {
+        SignatureMetadata::new(
+            signer_id.to_string(),
+            "ml-dsa-87+ed25519".to_string(),
+            vec![pubkey_byte; 4],
+            vec![pubkey_byte; 4],
+            vec![pubkey_byte; 8],
+            vec![pubkey_byte; 8],
+        )
+    }
+
+    #[test]
+    fn multi_signer_header_lines_round_trip() {
+        let entries = vec![
+            signature_metadata_entry("approver", 9),
+            signature_metadata_entry("author", 1),
+        ];
+        let lines = SignatureMetadata::to_header_lines(&entries);
+        let parsed = SignatureMetadata::from_header_lines(&lines).unwrap();
+
+        assert_eq!(parsed.len(), 2);
+        // Sorted by public-key bytes ascending, so "author" (0x01...) comes first.
+        assert_eq!(parsed[0].signer_id, "author");
+        assert_eq!(parsed[1].signer_id, "approver");
+    }
+
+    #[test]
+    fn header_lines_are_order_independent() {
+        let mut entries = vec![
+            signature_metadata_entry("approver", 9),
+            signature_metadata_entry("author", 1),
+        ];
+        let lines_a = SignatureMetadata::to_header_lines(&entries);
+        entries.reverse();
+        let lines_b = SignatureMetadata::to_header_lines(&entries);
+        assert_eq!(lines_a, lines_b, "signer blocks must sort deterministically");
+    }
+
+    #[test]
+    fn distinct_signer_count_dedupes_by_signer_id() {
+        let entries = vec![
+            signature_metadata_entry("author", 1),
+            signature_metadata_entry("author", 2),
+            signature_metadata_entry("approver", 3),
+        ];
+        assert_eq!(SignatureMetadata::distinct_signer_count(&entries), 2);
+    }
+
+    #[test]
+    fn verify_all_fails_closed_on_empty_or_bad_signature() {
+        assert!(!SignatureMetadata::verify_all(&[], b"msg").unwrap());
+
+        let mut bad = signature_metadata_entry("author", 1);
+        bad.signature = general_purpose::STANDARD.encode(vec![0u8; 4595]);
+        assert!(!SignatureMetadata::verify_all(&[bad], b"msg").unwrap_or(false));
+    }
 }
@@ -0,0 +1,224 @@
+//! COSE_Sign1 (RFC 9052 §4.2) envelope encoding for header signatures, as an
+//! alternative to the crate's bespoke canonical-bytes-plus-raw-signature
+//! format. This exists purely for interop: a file signed this way can be
+//! verified by any generic COSE library, and the signature can be reused
+//! with COSE-based key management tooling. The raw hybrid ML-DSA-87 +
+//! Ed25519 encoding (see `signer::verify_hybrid_signature`) stays the
+//! default; this is only produced when a caller opts in via
+//! `SealRequest::cose_sign1`.
+//!
+//! Only the specific shapes this crate needs are implemented — a detached
+//! `COSE_Sign1` array with an empty unprotected bucket and a single `alg`
+//! entry in the protected header — not a general CBOR (RFC 8949) codec.
+
+use anyhow::{bail, Result};
+
+use crate::signer::{verify_signature, Signer};
+
+#[cfg(test)]
+use crate::signer::LocalSigner;
+
+/// Provisional COSE algorithm identifier for ML-DSA-87. There is no IANA
+/// COSE Algorithms registration for FIPS 204 yet, so this is a
+/// private-use value (RFC 9053 reserves negative integers for signature
+/// algorithms); it round-trips within this crate and is exposed so a
+/// verifier can sanity-check it against the protected header.
+pub const COSE_ALG_MLDSA87: i64 = -99;
+
+fn cbor_head(major_type: u8, value: u64) -> Vec<u8> {
+    let mt = major_type << 5;
+    if value < 24 {
+        vec![mt | value as u8]
+    } else if value <= u8::MAX as u64 {
+        vec![mt | 24, value as u8]
+    } else if value <= u16::MAX as u64 {
+        let mut out = vec![mt | 25];
+        out.extend_from_slice(&(value as u16).to_be_bytes());
+        out
+    } else if value <= u32::MAX as u64 {
+        let mut out = vec![mt | 26];
+        out.extend_from_slice(&(value as u32).to_be_bytes());
+        out
+    } else {
+        let mut out = vec![mt | 27];
+        out.extend_from_slice(&value.to_be_bytes());
+        out
+    }
+}
+
+fn cbor_int(n: i64) -> Vec<u8> {
+    if n >= 0 {
+        cbor_head(0, n as u64)
+    } else {
+        cbor_head(1, (-1 - n) as u64)
+    }
+}
+
+fn cbor_bytes(b: &[u8]) -> Vec<u8> {
+    let mut out = cbor_head(2, b.len() as u64);
+    out.extend_from_slice(b);
+    out
+}
+
+fn cbor_text(s: &str) -> Vec<u8> {
+    let mut out = cbor_head(3, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+    out
+}
+
+fn cbor_array(items: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = cbor_head(4, items.len() as u64);
+    for item in items {
+        out.extend_from_slice(item);
+    }
+    out
+}
+
+/// The COSE_Sign1 `protected` field: a byte string wrapping the CBOR map
+/// `{1: alg}` (label 1 is `alg` per RFC 9052 §3.1).
+fn protected_header(alg: i64) -> Vec<u8> {
+    let mut map = cbor_head(5, 1);
+    map.extend_from_slice(&cbor_int(1));
+    map.extend_from_slice(&cbor_int(alg));
+    cbor_bytes(&map)
+}
+
+/// `Sig_structure` (RFC 9052 §4.4) for a detached COSE_Sign1: `external_aad`
+/// carries the canonical header bytes, and the payload is empty since the
+/// header itself is never embedded in the envelope.
+fn sig_structure(protected: &[u8], external_aad: &[u8]) -> Vec<u8> {
+    cbor_array(&[
+        cbor_text("Signature1"),
+        protected.to_vec(),
+        cbor_bytes(external_aad),
+        cbor_bytes(&[]),
+    ])
+}
+
+/// Sign `canonical_header_bytes` and return the encoded COSE_Sign1 array
+/// `[protected, unprotected, payload, signature]`, with an empty
+/// unprotected map and a detached (empty) payload. Accepts any
+/// [`Signer`] (chunk3-1) — in-memory, file-backed, or remote — as long as
+/// it reports the `ml-dsa-87` algorithm this envelope's protected header
+/// hardcodes.
+pub fn sign(signer: &dyn Signer, canonical_header_bytes: &[u8]) -> Result<Vec<u8>> {
+    if signer.algorithm() != "ml-dsa-87" {
+        bail!(
+            "COSE_Sign1 encoding here only supports ml-dsa-87 signers, got {}",
+            signer.algorithm()
+        );
+    }
+    let protected = protected_header(COSE_ALG_MLDSA87);
+    let tbs = sig_structure(&protected, canonical_header_bytes);
+    let signature = signer.try_sign(&tbs)?;
+    let unprotected = cbor_head(5, 0);
+
+    Ok(cbor_array(&[
+        protected,
+        unprotected,
+        cbor_bytes(&[]),
+        cbor_bytes(&signature),
+    ]))
+}
+
+fn read_head(bytes: &[u8], expected_major: u8) -> Result<(u64, usize)> {
+    let Some(&first) = bytes.first() else {
+        bail!("unexpected end of CBOR input");
+    };
+    let major = first >> 5;
+    if major != expected_major {
+        bail!("unexpected CBOR major type {} (expected {})", major, expected_major);
+    }
+    match first & 0x1f {
+        n @ 0..=23 => Ok((n as u64, 1)),
+        24 => {
+            let b = bytes.get(1).ok_or_else(|| anyhow::anyhow!("truncated CBOR head"))?;
+            Ok((*b as u64, 2))
+        }
+        25 => {
+            let b = bytes.get(1..3).ok_or_else(|| anyhow::anyhow!("truncated CBOR head"))?;
+            Ok((u16::from_be_bytes(b.try_into().unwrap()) as u64, 3))
+        }
+        26 => {
+            let b = bytes.get(1..5).ok_or_else(|| anyhow::anyhow!("truncated CBOR head"))?;
+            Ok((u32::from_be_bytes(b.try_into().unwrap()) as u64, 5))
+        }
+        27 => {
+            let b = bytes.get(1..9).ok_or_else(|| anyhow::anyhow!("truncated CBOR head"))?;
+            Ok((u64::from_be_bytes(b.try_into().unwrap()), 9))
+        }
+        info => bail!("unsupported CBOR additional info {}", info),
+    }
+}
+
+fn read_bstr(bytes: &[u8]) -> Result<(Vec<u8>, usize)> {
+    let (len, head_len) = read_head(bytes, 2)?;
+    let len = len as usize;
+    let body = bytes
+        .get(head_len..head_len + len)
+        .ok_or_else(|| anyhow::anyhow!("truncated CBOR byte string"))?;
+    Ok((body.to_vec(), head_len + len))
+}
+
+/// Parse a COSE_Sign1 envelope produced by [`sign`], reconstruct the
+/// `Sig_structure` against `canonical_header_bytes`, and verify it with the
+/// given ML-DSA-87 public key.
+pub fn verify(envelope: &[u8], canonical_header_bytes: &[u8], pk_bytes: &[u8]) -> Result<bool> {
+    let (array_len, mut cursor) = read_head(envelope, 4)?;
+    if array_len != 4 {
+        bail!("COSE_Sign1 must be a 4-element array, got {}", array_len);
+    }
+
+    let (protected, consumed) = read_bstr(&envelope[cursor..])?;
+    cursor += consumed;
+
+    let (unprotected_len, consumed) = read_head(&envelope[cursor..], 5)?;
+    if unprotected_len != 0 {
+        bail!("unsupported COSE_Sign1 unprotected header (expected empty map)");
+    }
+    cursor += consumed;
+
+    let (_payload, consumed) = read_bstr(&envelope[cursor..])?;
+    cursor += consumed;
+
+    let (signature, _consumed) = read_bstr(&envelope[cursor..])?;
+
+    let tbs = sig_structure(&protected, canonical_header_bytes);
+    verify_signature(&tbs, &signature, pk_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pqcrypto_traits::sign::PublicKey as _;
+
+    #[test]
+    fn sign_and_verify_round_trip() {
+        let signer = LocalSigner::generate();
+        let header_bytes = b"qsfs/v2\nparams: test\n".to_vec();
+
+        let envelope = sign(&signer, &header_bytes).unwrap();
+        assert!(verify(&envelope, &header_bytes, signer.pk.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn tampered_header_bytes_fail_verification() {
+        let signer = LocalSigner::generate();
+        let header_bytes = b"qsfs/v2\nparams: test\n".to_vec();
+        let envelope = sign(&signer, &header_bytes).unwrap();
+
+        let mut tampered = header_bytes.clone();
+        tampered[0] ^= 0x80;
+        assert!(!verify(&envelope, &tampered, signer.pk.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn wrong_public_key_fails_verification() {
+        let signer = LocalSigner::generate();
+        let other = LocalSigner::generate();
+        let header_bytes = b"qsfs/v2\nparams: test\n".to_vec();
+        let envelope = sign(&signer, &header_bytes).unwrap();
+
+        assert!(!verify(&envelope, &header_bytes, other.pk.as_bytes()).unwrap());
+    }
+}
@@ -0,0 +1,175 @@
+//! Key derivation: CEK generation, KEK combining, and DEK wrap/unwrap.
+
+use aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce as N12};
+use aes_gcm_siv::Aes256GcmSiv;
+use anyhow::{Result, bail};
+use chacha20poly1305::ChaCha20Poly1305;
+use hkdf::Hkdf;
+use rand::RngCore;
+use secrecy::{ExposeSecret, Secret};
+use sha2::Sha512;
+use sha3::Sha3_384;
+
+use crate::suite::{AeadId, KdfId};
+
+/// The randomly-generated, per-file content-encryption key.
+pub struct ContentEncryptionKey(Secret<[u8; 32]>);
+
+impl ContentEncryptionKey {
+    pub fn generate() -> Result<Self> {
+        let mut k = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut k);
+        Ok(ContentEncryptionKey(Secret::new(k)))
+    }
+}
+
+impl ExposeSecret<[u8; 32]> for ContentEncryptionKey {
+    fn expose_secret(&self) -> &[u8; 32] {
+        self.0.expose_secret()
+    }
+}
+
+/// Keys expanded from the CEK for the streaming layer.
+pub struct DerivedKeys {
+    pub aes_k1: Secret<[u8; 32]>,
+}
+
+/// Expand the CEK into the per-stream AEAD key, domain-separated by `info`.
+pub fn hkdf_expand_keys(cek: &[u8; 32], info: Option<&[u8]>) -> DerivedKeys {
+    let hk = Hkdf::<Sha3_384>::new(None, cek);
+    let mut k1 = [0u8; 32];
+    hk.expand(info.unwrap_or(b"qsfs_stream_k1"), &mut k1)
+        .expect("32 is a valid HKDF-SHA3-384 output length");
+    DerivedKeys { aes_k1: Secret::new(k1) }
+}
+
+/// Derive the 8-byte per-file nonce seed used to build chunk nonces.
+pub fn derive_file_nonce_seed(cek: &[u8; 32]) -> [u8; 8] {
+    let hk = Hkdf::<Sha3_384>::new(None, cek);
+    let mut seed = [0u8; 8];
+    hk.expand(b"qsfs_file_id", &mut seed)
+        .expect("8 is a valid HKDF-SHA3-384 output length");
+    seed
+}
+
+const KEK_LABEL: &[u8] = b"qsfs/kek/v2";
+/// Domain separator for the X-Wing-style transcript combiner: distinguishes
+/// this binding from any other use of the same shared secrets.
+const XWING_LABEL: &[u8] = b"qsfs/kek/v2/xwing";
+
+/// Every value that must be bound into the KEK so it commits to the exact
+/// encapsulation that produced it — re-encapsulating to a different
+/// ciphertext or public key yields a different KEK even if the raw shared
+/// secrets happened to collide.
+pub struct KemTranscript<'a> {
+    pub mlkem_ss: &'a [u8],
+    pub x25519_ss: &'a [u8],
+    pub mlkem_ct: &'a [u8],
+    /// The ephemeral X25519 public key carried in the header (empty when
+    /// the build has no X25519 hybrid leg).
+    pub eph_x25519_pk: &'a [u8],
+    /// The recipient's static X25519 public key (empty likewise).
+    pub recipient_x25519_pk: &'a [u8],
+}
+
+/// Combine a full KEM transcript into a 32-byte KEK, X-Wing-style: HKDF over
+/// `label || ss_mlkem || ss_x25519 || mlkem_ct || eph_x25519_pk ||
+/// recipient_x25519_pk`, salted by the per-file `kdf_salt`.
+///
+/// Binding the ciphertext and both X25519 public keys (not just the raw
+/// shared secrets) closes re-encapsulation/substitution attacks where an
+/// attacker swaps in a different ciphertext or key that happens to
+/// decapsulate to the same secret.
+pub fn derive_kek(transcript: &KemTranscript, salt: Option<&[u8]>, kdf: KdfId) -> [u8; 32] {
+    let mut ikm = Vec::with_capacity(
+        XWING_LABEL.len()
+            + transcript.mlkem_ss.len()
+            + transcript.x25519_ss.len()
+            + transcript.mlkem_ct.len()
+            + transcript.eph_x25519_pk.len()
+            + transcript.recipient_x25519_pk.len(),
+    );
+    ikm.extend_from_slice(XWING_LABEL);
+    ikm.extend_from_slice(transcript.mlkem_ss);
+    ikm.extend_from_slice(transcript.x25519_ss);
+    ikm.extend_from_slice(transcript.mlkem_ct);
+    ikm.extend_from_slice(transcript.eph_x25519_pk);
+    ikm.extend_from_slice(transcript.recipient_x25519_pk);
+
+    let mut out = [0u8; 32];
+    match kdf {
+        KdfId::HkdfSha3_384 => {
+            let hk = Hkdf::<Sha3_384>::new(salt, &ikm);
+            hk.expand(KEK_LABEL, &mut out).expect("32 is a valid output length");
+        }
+        KdfId::HkdfSha512 => {
+            let hk = Hkdf::<Sha512>::new(salt, &ikm);
+            hk.expand(KEK_LABEL, &mut out).expect("32 is a valid output length");
+        }
+    }
+    out
+}
+
+/// The DEK-wrap AEAD, selected at runtime from the negotiated `SuiteId`
+/// rather than hardcoded — mirrors `streaming::BulkAead`, so a suite
+/// negotiated with ChaCha20-Poly1305 or AES-256-GCM-SIV wraps its DEK
+/// under the same AEAD it uses for the bulk stream, instead of silently
+/// falling back to AES-256-GCM.
+enum WrapAead {
+    Aes256Gcm(Aes256Gcm),
+    Aes256GcmSiv(Aes256GcmSiv),
+    ChaCha20Poly1305(ChaCha20Poly1305),
+}
+
+impl WrapAead {
+    fn new(aead_id: AeadId, kek: &[u8; 32]) -> Result<Self> {
+        Ok(match aead_id {
+            AeadId::Aes256Gcm => {
+                WrapAead::Aes256Gcm(Aes256Gcm::new_from_slice(kek).map_err(|_| anyhow::anyhow!("bad KEK length"))?)
+            }
+            AeadId::Aes256GcmSiv => WrapAead::Aes256GcmSiv(
+                Aes256GcmSiv::new_from_slice(kek).map_err(|_| anyhow::anyhow!("bad KEK length"))?,
+            ),
+            AeadId::ChaCha20Poly1305 => WrapAead::ChaCha20Poly1305(
+                ChaCha20Poly1305::new_from_slice(kek).map_err(|_| anyhow::anyhow!("bad KEK length"))?,
+            ),
+        })
+    }
+
+    fn encrypt(&self, nonce: &[u8; 12], dek: &[u8]) -> aead::Result<Vec<u8>> {
+        let n = N12::from_slice(nonce);
+        match self {
+            WrapAead::Aes256Gcm(a) => a.encrypt(n, dek),
+            WrapAead::Aes256GcmSiv(a) => a.encrypt(n, dek),
+            WrapAead::ChaCha20Poly1305(a) => a.encrypt(n, dek),
+        }
+    }
+
+    fn decrypt(&self, nonce: &[u8; 12], wrapped: &[u8]) -> aead::Result<Vec<u8>> {
+        let n = N12::from_slice(nonce);
+        match self {
+            WrapAead::Aes256Gcm(a) => a.decrypt(n, wrapped),
+            WrapAead::Aes256GcmSiv(a) => a.decrypt(n, wrapped),
+            WrapAead::ChaCha20Poly1305(a) => a.decrypt(n, wrapped),
+        }
+    }
+}
+
+/// Wrap the DEK under the KEK with the suite's negotiated AEAD.
+pub fn wrap_dek(kek: &[u8; 32], nonce: &[u8; 12], dek: &[u8], aead_id: AeadId) -> Result<Vec<u8>> {
+    let aead = WrapAead::new(aead_id, kek)?;
+    aead.encrypt(nonce, dek).map_err(|_| anyhow::anyhow!("DEK wrap failed"))
+}
+
+/// Unwrap a DEK previously produced by [`wrap_dek`].
+pub fn unwrap_dek(kek: &[u8; 32], nonce: &[u8; 12], wrapped: &[u8], aead_id: AeadId) -> Result<[u8; 32]> {
+    let aead = WrapAead::new(aead_id, kek)?;
+    let pt = aead.decrypt(nonce, wrapped).map_err(|_| anyhow::anyhow!("DEK unwrap failed"))?;
+    if pt.len() != 32 {
+        bail!("unwrapped DEK has unexpected length: {}", pt.len());
+    }
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&pt);
+    Ok(out)
+}
@@ -0,0 +1,295 @@
+//! Interactive authenticated key-agreement handshake (UKEY2-style): two
+//! online parties establish a shared session key using the same hybrid
+//! ML-KEM-1024 + X25519 KEM the file format uses, confirmed out-of-band via
+//! a short human-comparable verification string. The resulting session key
+//! is meant to be fed into `streaming::encrypt_stream`/`decrypt_stream`
+//! directly, the same way a file's per-recipient KEK feeds the DEK.
+//!
+//! Three messages, commitment-bound against key-substitution attacks:
+//!
+//! 1. `ClientInit` (initiator -> responder): the initiator's ephemeral
+//!    ML-KEM-1024 public key (sent in the clear — it's not sensitive), plus
+//!    a commitment `H(ClientFinished)` to its ephemeral X25519 public key.
+//! 2. `ServerInit` (responder -> initiator): the responder's ephemeral
+//!    X25519 public key, plus an ML-KEM ciphertext encapsulated against the
+//!    initiator's ML-KEM public key.
+//! 3. `ClientFinished` (initiator -> responder): the initiator's ephemeral
+//!    X25519 public key, revealing the value committed to in `ClientInit`.
+//!
+//! Committing to the X25519 share *before* seeing `ServerInit` is the
+//! invariant that matters: an initiator able to choose its share only after
+//! seeing the responder's could steer the derived session key (and the
+//! verification string it feeds) toward a value of their choosing. Binding
+//! the commitment in first closes that off.
+
+use anyhow::{bail, Result};
+use hkdf::Hkdf;
+use pqcrypto_mlkem::mlkem1024;
+use pqcrypto_traits::kem::{Ciphertext as _, PublicKey as _, SharedSecret as _};
+use rand::rngs::OsRng;
+use sha3::Sha3_384;
+use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret};
+
+use crate::pq::mlkem;
+
+const SESSION_KEY_LABEL: &[u8] = b"qsfs/handshake/v1/session-key";
+const SAS_LABEL: &[u8] = b"qsfs/handshake/v1/sas";
+
+/// `ClientInit`: the initiator's ephemeral ML-KEM-1024 public key plus a
+/// commitment to its (not yet revealed) ephemeral X25519 public key.
+#[derive(Clone)]
+pub struct ClientInit {
+    pub eph_mlkem_pk: Vec<u8>,
+    pub commitment: [u8; 32],
+}
+
+/// `ServerInit`: the responder's ephemeral X25519 public key, plus the
+/// ML-KEM ciphertext encapsulated against `ClientInit::eph_mlkem_pk`.
+#[derive(Clone)]
+pub struct ServerInit {
+    pub eph_x25519_pk: [u8; 32],
+    pub mlkem_ct: Vec<u8>,
+}
+
+/// `ClientFinished`: the initiator's ephemeral X25519 public key, revealing
+/// the value committed to in `ClientInit::commitment`.
+#[derive(Clone)]
+pub struct ClientFinished {
+    pub eph_x25519_pk: [u8; 32],
+}
+
+/// The session material both sides derive once the handshake completes.
+pub struct SessionKeys {
+    /// Feed this into `streaming::encrypt_stream`/`decrypt_stream` as the
+    /// per-stream AEAD key.
+    pub session_key: [u8; 32],
+    /// A short decimal string both parties can read aloud/compare
+    /// out-of-band to detect a machine-in-the-middle.
+    pub verification_string: String,
+}
+
+fn encode_client_init(msg: &ClientInit) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + msg.eph_mlkem_pk.len() + 32);
+    out.extend_from_slice(&(msg.eph_mlkem_pk.len() as u32).to_be_bytes());
+    out.extend_from_slice(&msg.eph_mlkem_pk);
+    out.extend_from_slice(&msg.commitment);
+    out
+}
+
+fn encode_server_init(msg: &ServerInit) -> Vec<u8> {
+    let mut out = Vec::with_capacity(32 + 4 + msg.mlkem_ct.len());
+    out.extend_from_slice(&msg.eph_x25519_pk);
+    out.extend_from_slice(&(msg.mlkem_ct.len() as u32).to_be_bytes());
+    out.extend_from_slice(&msg.mlkem_ct);
+    out
+}
+
+fn encode_client_finished(msg: &ClientFinished) -> Vec<u8> {
+    msg.eph_x25519_pk.to_vec()
+}
+
+/// `session_key = HKDF(mlkem_ss || x25519_ss, info = H(ClientInit) ||
+/// H(ServerInit) || H(ClientFinished))`, plus a 6-digit verification string
+/// derived from the same transcript under a different label.
+fn derive_session_keys(
+    mlkem_ss: &[u8],
+    x25519_ss: &[u8],
+    client_init_bytes: &[u8],
+    server_init_bytes: &[u8],
+    client_finished_bytes: &[u8],
+) -> SessionKeys {
+    let mut ikm = Vec::with_capacity(mlkem_ss.len() + x25519_ss.len());
+    ikm.extend_from_slice(mlkem_ss);
+    ikm.extend_from_slice(x25519_ss);
+
+    let mut transcript_hash = Vec::with_capacity(3 * 32);
+    transcript_hash.extend_from_slice(blake3::hash(client_init_bytes).as_bytes());
+    transcript_hash.extend_from_slice(blake3::hash(server_init_bytes).as_bytes());
+    transcript_hash.extend_from_slice(blake3::hash(client_finished_bytes).as_bytes());
+
+    let hk = Hkdf::<Sha3_384>::new(None, &ikm);
+
+    let mut session_key = [0u8; 32];
+    hk.expand(&[SESSION_KEY_LABEL, &transcript_hash].concat(), &mut session_key)
+        .expect("32 is a valid HKDF-SHA3-384 output length");
+
+    let mut sas_bytes = [0u8; 4];
+    hk.expand(&[SAS_LABEL, &transcript_hash].concat(), &mut sas_bytes)
+        .expect("4 is a valid HKDF-SHA3-384 output length");
+    let sas_number = u32::from_be_bytes(sas_bytes) % 1_000_000;
+
+    SessionKeys {
+        session_key,
+        verification_string: format!("{:06}", sas_number),
+    }
+}
+
+/// Initiator state after [`Initiator::start`], pending the responder's
+/// `ServerInit`.
+pub struct Initiator {
+    eph_mlkem_sk: mlkem1024::SecretKey,
+    eph_x25519_sk: StaticSecret,
+    client_init_bytes: Vec<u8>,
+    client_finished_bytes: Vec<u8>,
+}
+
+impl Initiator {
+    /// Generate the initiator's ephemeral keys and commitment, returning the
+    /// `ClientInit` message to send to the responder.
+    pub fn start() -> (Self, ClientInit) {
+        let (eph_mlkem_pk, eph_mlkem_sk) = mlkem::keypair();
+        let eph_x25519_sk = StaticSecret::random_from_rng(OsRng);
+        let eph_x25519_pk = X25519PublicKey::from(&eph_x25519_sk);
+
+        let client_finished = ClientFinished {
+            eph_x25519_pk: *eph_x25519_pk.as_bytes(),
+        };
+        let client_finished_bytes = encode_client_finished(&client_finished);
+        let commitment = *blake3::hash(&client_finished_bytes).as_bytes();
+
+        let client_init = ClientInit {
+            eph_mlkem_pk: eph_mlkem_pk.as_bytes().to_vec(),
+            commitment,
+        };
+        let client_init_bytes = encode_client_init(&client_init);
+
+        (
+            Initiator {
+                eph_mlkem_sk,
+                eph_x25519_sk,
+                client_init_bytes,
+                client_finished_bytes,
+            },
+            client_init,
+        )
+    }
+
+    /// Consume the responder's `ServerInit`, deriving the session keys and
+    /// the `ClientFinished` message to send back.
+    pub fn finish(self, server_init: &ServerInit) -> Result<(ClientFinished, SessionKeys)> {
+        let ct = mlkem1024::Ciphertext::from_bytes(&server_init.mlkem_ct)
+            .map_err(|_| anyhow::anyhow!("invalid ML-KEM-1024 ciphertext in ServerInit"))?;
+        let mlkem_ss = mlkem::decapsulate(&ct, &self.eph_mlkem_sk);
+
+        let server_x_pk = X25519PublicKey::from(server_init.eph_x25519_pk);
+        let x25519_ss = self.eph_x25519_sk.diffie_hellman(&server_x_pk);
+
+        let server_init_bytes = encode_server_init(server_init);
+        let session = derive_session_keys(
+            mlkem_ss.as_bytes(),
+            x25519_ss.as_bytes(),
+            &self.client_init_bytes,
+            &server_init_bytes,
+            &self.client_finished_bytes,
+        );
+
+        let client_finished = ClientFinished {
+            eph_x25519_pk: *X25519PublicKey::from(&self.eph_x25519_sk).as_bytes(),
+        };
+        Ok((client_finished, session))
+    }
+}
+
+/// Responder state after [`Responder::respond`], pending the initiator's
+/// `ClientFinished`.
+pub struct Responder {
+    eph_x25519_sk: StaticSecret,
+    mlkem_ss: mlkem1024::SharedSecret,
+    commitment: [u8; 32],
+    client_init_bytes: Vec<u8>,
+    server_init_bytes: Vec<u8>,
+}
+
+impl Responder {
+    /// Encapsulate against the initiator's ML-KEM public key and generate an
+    /// ephemeral X25519 key, returning the `ServerInit` message to send back.
+    pub fn respond(client_init: &ClientInit) -> Result<(Self, ServerInit)> {
+        let client_mlkem_pk = mlkem1024::PublicKey::from_bytes(&client_init.eph_mlkem_pk)
+            .map_err(|_| anyhow::anyhow!("invalid ML-KEM-1024 public key in ClientInit"))?;
+        let (mlkem_ss, mlkem_ct) = mlkem::encapsulate(&client_mlkem_pk);
+
+        let eph_x25519_sk = StaticSecret::random_from_rng(OsRng);
+        let eph_x25519_pk = X25519PublicKey::from(&eph_x25519_sk);
+
+        let server_init = ServerInit {
+            eph_x25519_pk: *eph_x25519_pk.as_bytes(),
+            mlkem_ct: mlkem_ct.as_bytes().to_vec(),
+        };
+        let client_init_bytes = encode_client_init(client_init);
+        let server_init_bytes = encode_server_init(&server_init);
+
+        Ok((
+            Responder {
+                eph_x25519_sk,
+                mlkem_ss,
+                commitment: client_init.commitment,
+                client_init_bytes,
+                server_init_bytes,
+            },
+            server_init,
+        ))
+    }
+
+    /// Verify the initiator's `ClientFinished` against the commitment
+    /// carried in `ClientInit` and derive the session keys.
+    pub fn finish(self, client_finished: &ClientFinished) -> Result<SessionKeys> {
+        let client_finished_bytes = encode_client_finished(client_finished);
+        if *blake3::hash(&client_finished_bytes).as_bytes() != self.commitment {
+            bail!("ClientFinished does not match the commitment carried in ClientInit");
+        }
+
+        let client_x_pk = X25519PublicKey::from(client_finished.eph_x25519_pk);
+        let x25519_ss = self.eph_x25519_sk.diffie_hellman(&client_x_pk);
+
+        Ok(derive_session_keys(
+            self.mlkem_ss.as_bytes(),
+            x25519_ss.as_bytes(),
+            &self.client_init_bytes,
+            &self.server_init_bytes,
+            &client_finished_bytes,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handshake_round_trip_agrees_on_session_key() {
+        let (initiator, client_init) = Initiator::start();
+        let (responder, server_init) = Responder::respond(&client_init).unwrap();
+        let (client_finished, initiator_session) = initiator.finish(&server_init).unwrap();
+        let responder_session = responder.finish(&client_finished).unwrap();
+
+        assert_eq!(initiator_session.session_key, responder_session.session_key);
+        assert_eq!(
+            initiator_session.verification_string,
+            responder_session.verification_string
+        );
+    }
+
+    #[test]
+    fn tampered_client_finished_fails_commitment_check() {
+        let (initiator, client_init) = Initiator::start();
+        let (responder, server_init) = Responder::respond(&client_init).unwrap();
+        let (mut client_finished, _) = initiator.finish(&server_init).unwrap();
+        client_finished.eph_x25519_pk[0] ^= 0x80;
+
+        assert!(responder.finish(&client_finished).is_err());
+    }
+
+    #[test]
+    fn different_handshakes_yield_different_session_keys() {
+        let (initiator_a, client_init_a) = Initiator::start();
+        let (responder_a, server_init_a) = Responder::respond(&client_init_a).unwrap();
+        let (_, session_a) = initiator_a.finish(&server_init_a).unwrap();
+
+        let (initiator_b, client_init_b) = Initiator::start();
+        let (responder_b, server_init_b) = Responder::respond(&client_init_b).unwrap();
+        let (_, session_b) = initiator_b.finish(&server_init_b).unwrap();
+
+        let _ = (responder_a, responder_b);
+        assert_ne!(session_a.session_key, session_b.session_key);
+    }
+}
@@ -0,0 +1,123 @@
+//! On-disk QSFS v2 header.
+
+use serde::{Deserialize, Serialize};
+
+use crate::pae::pae_v2_compat;
+use crate::suite::{CompressionId, SuiteId};
+
+/// A single recipient's wrapped copy of the content-encryption key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipientEntry {
+    pub label: String,
+    pub mlkem_ct: Vec<u8>,
+    /// Legacy mirror of `wrapped_dek`, kept for header-format compatibility.
+    pub wrap: Vec<u8>,
+    pub wrapped_dek: Vec<u8>,
+    pub wrap_nonce: [u8; 12],
+    pub x25519_pk_fpr: [u8; 8],
+    pub x25519_pub: Vec<u8>,
+    /// RFC 9180 HPKE encapsulated key (`enc`): `mlkem_ct || eph_x25519_pk`,
+    /// kept alongside `mlkem_ct` so recipients can be processed through
+    /// `hpke::setup_base_r` without reassembling it from the header.
+    pub enc: Vec<u8>,
+}
+
+/// Signature metadata attached to a header. `public_key` is the signer's
+/// ML-DSA-87 public key; `ed25519_public_key` is its Ed25519 companion key
+/// (both signatures must verify against these — see `signer::verify_hybrid_signature`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureMetadata {
+    pub signer_id: String,
+    pub algorithm: String,
+    pub public_key: Vec<u8>,
+    pub ed25519_public_key: Vec<u8>,
+}
+
+/// A passphrase-based recipient (chunk2-4): the DEK is wrapped under a KEK
+/// derived from a user passphrase with Argon2id over the header's
+/// `kdf_salt`, rather than a hybrid KEM shared secret. The Argon2id cost
+/// parameters travel with the entry so a file remains openable even if the
+/// crate's own defaults change later; wrapping itself reuses
+/// `wrap_dek`/`unwrap_dek` unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassphraseRecipient {
+    pub label: String,
+    pub wrapped_dek: Vec<u8>,
+    pub wrap_nonce: [u8; 12],
+    pub argon2_mem_kib: u32,
+    pub argon2_time_cost: u32,
+    pub argon2_parallelism: u32,
+}
+
+/// An additional signature attached to an already-sealed file's header
+/// (chunk5-2), layered on top of the primary `mldsa_sig`/`ed25519_sig`
+/// pair. Unlike [`SignatureMetadata`] (which only records who signed —
+/// the signature bytes live in `Header::mldsa_sig`/`ed25519_sig`), each
+/// `CoSignature` is fully self-contained, since a header can carry several
+/// and they aren't all produced at seal time: `attach_co_signature` signs
+/// and appends one to an already-sealed container without touching its
+/// encrypted stream, so a quorum of independent signers can co-sign after
+/// the fact.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoSignature {
+    pub signer_id: String,
+    pub algorithm: String,
+    pub public_key: Vec<u8>,
+    pub ed25519_public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub ed25519_signature: Vec<u8>,
+}
+
+/// One file's entry in a signed bundle manifest (see `Header::manifest` and
+/// `seal_bundle`/`unseal_bundle`): binds an archive-relative path to the
+/// length and BLAKE3 digest of its plaintext, so the whole file set is
+/// authenticated as a unit under the header's existing signature rather
+/// than file by file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub relative_path: String,
+    pub length: u64,
+    pub blake3_digest: [u8; 32],
+}
+
+/// The QSFS v2 file header: recipient key table plus stream parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Header {
+    pub magic: [u8; 6],
+    pub chunk_size: u32,
+    pub file_id: [u8; 8],
+    pub blake3_of_plain: [u8; 32],
+    pub suite: SuiteId,
+    /// Per-file KDF salt (v2.1+). `None` reproduces the v2.0 layout.
+    pub kdf_salt: Option<[u8; 32]>,
+    /// Opt-in pre-encryption compression (chunk2-5). `None` (the default)
+    /// means every chunk is sealed uncompressed, reproducing the
+    /// pre-chunk2-5 layout; `Some` is bound into the AEAD AAD (see
+    /// `pae::pae_v2_compat`) so it can't be flipped after sealing.
+    pub compression: Option<CompressionId>,
+    pub recipients: Vec<RecipientEntry>,
+    /// Passphrase-based recipients (v2.2+), see [`PassphraseRecipient`].
+    /// Empty for files sealed only to hybrid KEM recipients.
+    pub passphrase_recipients: Vec<PassphraseRecipient>,
+    pub eph_x25519_pk: [u8; 32],
+    pub mldsa_sig: Vec<u8>,
+    pub ed25519_sig: Vec<u8>,
+    pub signature_metadata: Option<SignatureMetadata>,
+    /// Additional quorum co-signatures (chunk5-2), see [`CoSignature`].
+    /// Empty for a file with only the primary signature above, or none at
+    /// all.
+    pub co_signatures: Vec<CoSignature>,
+    /// Bundle mode: a signed manifest of the files packed into this
+    /// container's single encrypted stream, in the order their plaintext
+    /// bytes appear there. `None` for an ordinary single-file container.
+    pub manifest: Option<Vec<ManifestEntry>>,
+    pub fin: u8,
+}
+
+impl Header {
+    /// AAD bound into every chunk's AEAD seal: the stream parameters that
+    /// must not be substituted after the fact.
+    pub fn aead_aad(&self) -> Vec<u8> {
+        pae_v2_compat(self)
+    }
+}
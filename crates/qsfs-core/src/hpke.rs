@@ -0,0 +1,217 @@
+//! A minimal RFC 9180 HPKE-style key schedule layered over the crate's
+//! hybrid ML-KEM-1024 + X25519 KEM.
+//!
+//! This is not a general-purpose HPKE implementation — there's no PSK/auth
+//! mode and exactly one ciphersuite — just the `LabeledExtract`/
+//! `LabeledExpand` key schedule and `Export` interface RFC 9180 §4/§5.3
+//! define, run on top of [`derive_kek`]'s transcript-binding combiner
+//! output (the "shared secret" the KEM produces). The payoff over calling
+//! `derive_kek` directly is that `Context::export` additionally folds `info`
+//! (the file_id and suite, see [`info_for`]) into the derived secret through
+//! HPKE's key schedule, so recipient key-wrap derivation inherits HPKE's
+//! standard `enc`/`info` binding rather than an ad-hoc concatenation.
+
+use hkdf::Hkdf;
+use sha3::Sha3_384;
+
+use crate::derivation::{derive_kek, KemTranscript};
+use crate::suite::{KdfId, SuiteId};
+
+const VERSION_LABEL: &[u8] = b"HPKE-v1";
+const SUITE_ID: &[u8] = b"QSFS-HPKE-MLKEM1024-X25519-SHA3-384";
+const EXPORTER_SECRET_LEN: usize = 48; // SHA3-384 output size
+
+fn labeled_extract(salt: &[u8], label: &[u8], ikm: &[u8]) -> [u8; EXPORTER_SECRET_LEN] {
+    let mut labeled_ikm = Vec::with_capacity(VERSION_LABEL.len() + SUITE_ID.len() + label.len() + ikm.len());
+    labeled_ikm.extend_from_slice(VERSION_LABEL);
+    labeled_ikm.extend_from_slice(SUITE_ID);
+    labeled_ikm.extend_from_slice(label);
+    labeled_ikm.extend_from_slice(ikm);
+
+    let (prk, _) = Hkdf::<Sha3_384>::extract(Some(salt), &labeled_ikm);
+    let mut out = [0u8; EXPORTER_SECRET_LEN];
+    out.copy_from_slice(&prk);
+    out
+}
+
+fn labeled_expand(prk: &[u8], label: &[u8], info: &[u8], length: usize) -> Vec<u8> {
+    let mut labeled_info = Vec::with_capacity(2 + VERSION_LABEL.len() + SUITE_ID.len() + label.len() + info.len());
+    labeled_info.extend_from_slice(&(length as u16).to_be_bytes());
+    labeled_info.extend_from_slice(VERSION_LABEL);
+    labeled_info.extend_from_slice(SUITE_ID);
+    labeled_info.extend_from_slice(label);
+    labeled_info.extend_from_slice(info);
+
+    let hk = Hkdf::<Sha3_384>::from_prk(prk).expect("PRK is the HKDF-SHA3-384 output length");
+    let mut out = vec![0u8; length];
+    hk.expand(&labeled_info, &mut out).expect("requested export length is valid");
+    out
+}
+
+/// The per-recipient HPKE context produced by [`setup_base_s`]/[`setup_base_r`].
+/// Mirrors RFC 9180's `mode_base` key schedule: the `Export` operation plus
+/// the base-mode AEAD `key`/`base_nonce` pair (there is no `Seal`/`Open`).
+pub struct Context {
+    exporter_secret: [u8; EXPORTER_SECRET_LEN],
+    /// RFC 9180 `key`/`base_nonce` — the base-mode AEAD key/nonce pair the
+    /// key schedule derives alongside `exporter_secret`. Unused by
+    /// `wrap_dek`/`unwrap_dek` today (those still take an explicit KEK and
+    /// caller-supplied nonce, the crate's existing v2.0-compatible wire
+    /// format), but exposed for a future recipient-wrap path that derives
+    /// both from the HPKE context directly instead of storing a nonce.
+    base_key: [u8; 32],
+    base_nonce: [u8; 12],
+}
+
+impl Context {
+    /// `Export(exporter_context, length)` per RFC 9180 §5.3.
+    pub fn export(&self, exporter_context: &[u8], length: usize) -> Vec<u8> {
+        labeled_expand(&self.exporter_secret, b"sec", exporter_context, length)
+    }
+
+    /// The base-mode AEAD key, `key = LabeledExpand(secret, "key", key_schedule_context, Nk)`.
+    pub fn base_key(&self) -> &[u8; 32] {
+        &self.base_key
+    }
+
+    /// The base-mode AEAD nonce, `base_nonce = LabeledExpand(secret, "base_nonce", key_schedule_context, Nn)`.
+    pub fn base_nonce(&self) -> &[u8; 12] {
+        &self.base_nonce
+    }
+}
+
+/// `KeySchedule(mode_base, shared_secret, info)`, deriving `exporter_secret`
+/// plus the base-mode `key`/`base_nonce` pair (no PSK).
+fn key_schedule(shared_secret: &[u8], info: &[u8]) -> Context {
+    const MODE_BASE: u8 = 0x00;
+    let psk_id_hash = labeled_extract(&[], b"psk_id_hash", &[]);
+    let info_hash = labeled_extract(&[], b"info_hash", info);
+
+    let mut key_schedule_context = Vec::with_capacity(1 + 2 * EXPORTER_SECRET_LEN);
+    key_schedule_context.push(MODE_BASE);
+    key_schedule_context.extend_from_slice(&psk_id_hash);
+    key_schedule_context.extend_from_slice(&info_hash);
+
+    let secret = labeled_extract(shared_secret, b"secret", &[]); // psk = "" in mode_base
+
+    let exporter_secret_vec = labeled_expand(&secret, b"exp", &key_schedule_context, EXPORTER_SECRET_LEN);
+    let mut exporter_secret = [0u8; EXPORTER_SECRET_LEN];
+    exporter_secret.copy_from_slice(&exporter_secret_vec);
+
+    let key_vec = labeled_expand(&secret, b"key", &key_schedule_context, 32);
+    let mut base_key = [0u8; 32];
+    base_key.copy_from_slice(&key_vec);
+
+    let nonce_vec = labeled_expand(&secret, b"base_nonce", &key_schedule_context, 12);
+    let mut base_nonce = [0u8; 12];
+    base_nonce.copy_from_slice(&nonce_vec);
+
+    Context { exporter_secret, base_key, base_nonce }
+}
+
+/// Sender-side `SetupBaseS(pkR, info)`: returns the encapsulated key `enc`
+/// (the hybrid KEM ciphertext, i.e. the ML-KEM ciphertext plus the
+/// ephemeral X25519 public key) and the resulting `Context`.
+pub fn setup_base_s(transcript: &KemTranscript, salt: Option<&[u8]>, kdf: KdfId, info: &[u8]) -> (Vec<u8>, Context) {
+    let shared_secret = derive_kek(transcript, salt, kdf);
+    let mut enc = Vec::with_capacity(transcript.mlkem_ct.len() + transcript.eph_x25519_pk.len());
+    enc.extend_from_slice(transcript.mlkem_ct);
+    enc.extend_from_slice(transcript.eph_x25519_pk);
+    (enc, key_schedule(&shared_secret, info))
+}
+
+/// Recipient-side `SetupBaseR(enc, skR, info)`: same `Context` as the
+/// sender, given the decapsulated transcript.
+pub fn setup_base_r(transcript: &KemTranscript, salt: Option<&[u8]>, kdf: KdfId, info: &[u8]) -> Context {
+    let shared_secret = derive_kek(transcript, salt, kdf);
+    key_schedule(&shared_secret, info)
+}
+
+/// The HPKE `info` folded into every recipient's key schedule: binds the
+/// file_id and negotiated suite so an `enc` replayed against a different
+/// file or suite derives an unrelated key.
+pub fn info_for(file_id: &[u8; 8], suite: SuiteId) -> Vec<u8> {
+    let mut info = Vec::with_capacity(12 + 8 + suite.full_descriptor().len());
+    info.extend_from_slice(b"qsfs/v2/hpke");
+    info.extend_from_slice(file_id);
+    info.extend_from_slice(suite.full_descriptor().as_bytes());
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setup_base_s_and_r_agree_on_exported_secret() {
+        let mlkem_ss = [7u8; 32];
+        let x25519_ss = [9u8; 32];
+        let mlkem_ct = [1u8; 8];
+        let eph_pk = [2u8; 32];
+        let recip_pk = [3u8; 32];
+        let transcript = KemTranscript {
+            mlkem_ss: &mlkem_ss,
+            x25519_ss: &x25519_ss,
+            mlkem_ct: &mlkem_ct,
+            eph_x25519_pk: &eph_pk,
+            recipient_x25519_pk: &recip_pk,
+        };
+        let info = info_for(&[0u8; 8], SuiteId::current());
+
+        let (enc, s_ctx) = setup_base_s(&transcript, None, KdfId::HkdfSha3_384, &info);
+        let r_ctx = setup_base_r(&transcript, None, KdfId::HkdfSha3_384, &info);
+
+        assert_eq!(enc, [mlkem_ct.as_slice(), eph_pk.as_slice()].concat());
+        assert_eq!(
+            s_ctx.export(b"qsfs-dek-wrap", 32),
+            r_ctx.export(b"qsfs-dek-wrap", 32)
+        );
+    }
+
+    #[test]
+    fn different_info_yields_different_export() {
+        let mlkem_ss = [7u8; 32];
+        let x25519_ss = [9u8; 32];
+        let mlkem_ct = [1u8; 8];
+        let eph_pk = [2u8; 32];
+        let recip_pk = [3u8; 32];
+        let transcript = KemTranscript {
+            mlkem_ss: &mlkem_ss,
+            x25519_ss: &x25519_ss,
+            mlkem_ct: &mlkem_ct,
+            eph_x25519_pk: &eph_pk,
+            recipient_x25519_pk: &recip_pk,
+        };
+
+        let info_a = info_for(&[0u8; 8], SuiteId::current());
+        let info_b = info_for(&[1u8; 8], SuiteId::current());
+        let (_, ctx_a) = setup_base_s(&transcript, None, KdfId::HkdfSha3_384, &info_a);
+        let (_, ctx_b) = setup_base_s(&transcript, None, KdfId::HkdfSha3_384, &info_b);
+
+        assert_ne!(ctx_a.export(b"qsfs-dek-wrap", 32), ctx_b.export(b"qsfs-dek-wrap", 32));
+    }
+
+    #[test]
+    fn setup_base_s_and_r_agree_on_base_key_and_nonce() {
+        let mlkem_ss = [7u8; 32];
+        let x25519_ss = [9u8; 32];
+        let mlkem_ct = [1u8; 8];
+        let eph_pk = [2u8; 32];
+        let recip_pk = [3u8; 32];
+        let transcript = KemTranscript {
+            mlkem_ss: &mlkem_ss,
+            x25519_ss: &x25519_ss,
+            mlkem_ct: &mlkem_ct,
+            eph_x25519_pk: &eph_pk,
+            recipient_x25519_pk: &recip_pk,
+        };
+        let info = info_for(&[0u8; 8], SuiteId::current());
+
+        let (_, s_ctx) = setup_base_s(&transcript, None, KdfId::HkdfSha3_384, &info);
+        let r_ctx = setup_base_r(&transcript, None, KdfId::HkdfSha3_384, &info);
+
+        assert_eq!(s_ctx.base_key(), r_ctx.base_key());
+        assert_eq!(s_ctx.base_nonce(), r_ctx.base_nonce());
+        assert_ne!(s_ctx.base_key().to_vec(), s_ctx.export(b"qsfs-dek-wrap", 32));
+    }
+}
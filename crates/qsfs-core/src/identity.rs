@@ -0,0 +1,132 @@
+//! Deterministic identity derivation from a BIP-39 recovery phrase
+//! (chunk3-5): SLIP-0010 ed25519-style hardened derivation over a path
+//! `m/QSFS'/account'/index'`, the same "one phrase regenerates every
+//! keypair" model Solana uses for its derived keypairs. A user who backs up
+//! the mnemonic can regenerate every QSFS identity they derived from it,
+//! rather than needing to separately back up each one.
+//!
+//! Derivation, step by step:
+//! - `seed = BIP39(mnemonic, passphrase)` — the standard 64-byte BIP-39 seed.
+//! - `(key, chain_code) = HMAC-SHA512("qsfs seed", seed)` — the master node.
+//! - At each hardened step: `HMAC-SHA512(chain_code, 0x00 || key ||
+//!   u32_be(index | 0x80000000))`, split into the child's `(key, chain_code)`.
+//!
+//! The resulting 32-byte secret seeds the X25519 keypair directly, fully
+//! reproducibly. It can't do the same for the ML-KEM-1024 half: `pq::mlkem`
+//! (see its doc comment for the same limitation in `verify_decapsulate`)
+//! only exposes `pqcrypto_mlkem`'s OS-randomized `keypair()`, with no
+//! seeded or RNG-parameterized entry point. `derive_identity` is honest
+//! about this — it returns a freshly generated ML-KEM keypair alongside the
+//! deterministic X25519 one, rather than faking reproducibility it can't
+//! deliver.
+
+use anyhow::{Context, Result};
+use bip39::Mnemonic;
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+
+use crate::pq::mlkem;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Domain separator for the master node, per SLIP-0010's ed25519 curve.
+const MASTER_KEY_LABEL: &[u8] = b"qsfs seed";
+
+/// One derivation step's output: the 32-byte key and the 32-byte chain
+/// code carried forward to the next hardened step.
+struct Node {
+    key: [u8; 32],
+    chain_code: [u8; 32],
+}
+
+fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+    let mut mac = HmacSha512::new_from_slice(key).expect("HMAC-SHA512 accepts keys of any length");
+    mac.update(data);
+    let digest = mac.finalize().into_bytes();
+    let mut out = [0u8; 64];
+    out.copy_from_slice(&digest);
+    out
+}
+
+fn split_node(i: [u8; 64]) -> Node {
+    let mut key = [0u8; 32];
+    let mut chain_code = [0u8; 32];
+    key.copy_from_slice(&i[..32]);
+    chain_code.copy_from_slice(&i[32..]);
+    Node { key, chain_code }
+}
+
+fn master_node(seed: &[u8]) -> Node {
+    split_node(hmac_sha512(MASTER_KEY_LABEL, seed))
+}
+
+/// One hardened child step: `HMAC-SHA512(chain_code, 0x00 || key ||
+/// u32_be(index | 0x80000000))`. Non-hardened derivation (which would hash
+/// the public key instead of `0x00 || key`) is intentionally unsupported —
+/// every step in `m/QSFS'/account'/index'` is hardened.
+fn derive_hardened_child(parent: &Node, index: u32) -> Node {
+    let mut data = Vec::with_capacity(1 + 32 + 4);
+    data.push(0x00);
+    data.extend_from_slice(&parent.key);
+    data.extend_from_slice(&(index | 0x8000_0000).to_be_bytes());
+    split_node(hmac_sha512(&parent.chain_code, &data))
+}
+
+/// Walk `m/QSFS'/account'/index'` from the master node.
+fn derive_path(master: Node, account: u32, index: u32) -> Node {
+    let qsfs = derive_hardened_child(&master, 0);
+    let acct = derive_hardened_child(&qsfs, account);
+    derive_hardened_child(&acct, index)
+}
+
+/// A single derived QSFS identity, ready to populate the `RecipientEntry`
+/// fields (`x25519_pub`, `x25519_pk_fpr` — via `blake3::hash`, the same
+/// convention `seal_stream` uses — and the ML-KEM public key) and
+/// `Header::eph_x25519_pk`.
+pub struct DerivedIdentity {
+    pub path: String,
+    /// The 32-byte secret this identity's X25519 keypair was seeded from.
+    /// Reproducible from `(mnemonic, passphrase, account, index)` alone.
+    pub secret: [u8; 32],
+    pub x25519_sk: x25519_dalek::StaticSecret,
+    pub x25519_pk: x25519_dalek::PublicKey,
+    /// Freshly generated, *not* reproducible — see the module doc comment.
+    pub mlkem_pk: mlkem::PublicKey,
+    pub mlkem_sk: mlkem::SecretKey,
+}
+
+impl DerivedIdentity {
+    /// The fingerprint convention `seal_stream` already uses for
+    /// `RecipientEntry::x25519_pk_fpr`: the low 8 bytes of BLAKE3(pubkey).
+    pub fn x25519_pk_fpr(&self) -> [u8; 8] {
+        let h = blake3::hash(self.x25519_pk.as_bytes());
+        let mut f = [0u8; 8];
+        f.copy_from_slice(&h.as_bytes()[..8]);
+        f
+    }
+}
+
+/// Derive the identity at `m/QSFS'/{account}'/{index}'` from `mnemonic`
+/// (and optional BIP-39 `passphrase`, `""` if none). The X25519 keypair is
+/// fully deterministic: the same inputs always yield the same keypair, so
+/// a user who backs up only the mnemonic can regenerate every identity
+/// they derived from it.
+pub fn derive_identity(mnemonic: &str, passphrase: &str, account: u32, index: u32) -> Result<DerivedIdentity> {
+    let mnemonic = Mnemonic::parse(mnemonic).context("invalid BIP39 mnemonic")?;
+    let seed = mnemonic.to_seed(passphrase);
+
+    let node = derive_path(master_node(&seed), account, index);
+
+    let x25519_sk = x25519_dalek::StaticSecret::from(node.key);
+    let x25519_pk = x25519_dalek::PublicKey::from(&x25519_sk);
+    let (mlkem_pk, mlkem_sk) = mlkem::keypair();
+
+    Ok(DerivedIdentity {
+        path: format!("m/QSFS'/{}'/{}'", account, index),
+        secret: node.key,
+        x25519_sk,
+        x25519_pk,
+        mlkem_pk,
+        mlkem_sk,
+    })
+}
@@ -0,0 +1,196 @@
+//! Machine-readable header inspection (chunk5-4).
+//!
+//! Mirrors the CLI `Inspect` command's fields as a stable, serializable
+//! document so tooling (fleet auditing, CI checks) can consume them without
+//! screen-scraping printed lines. Unlike the old human-oriented command,
+//! this never fails outright on an invariant violation — every check it
+//! used to `bail!` on is instead recorded in `errors`/`warnings`, so a
+//! malformed header can still be inspected rather than only rejected.
+
+use serde::Serialize;
+
+use crate::header::Header;
+
+const MLKEM1024_CT_LEN: usize = 1568;
+const MLDSA87_PK_LEN: usize = 2592;
+/// AES-256-GCM wrap of a 32-byte DEK: 32-byte ciphertext + 16-byte tag.
+const WRAPPED_DEK_LEN: usize = 48;
+
+/// One recipient's entry in an [`InspectReport`].
+#[derive(Debug, Clone, Serialize)]
+pub struct RecipientReport {
+    pub label: String,
+    pub ct_len: usize,
+    pub wrap_len: usize,
+    pub x25519_len: usize,
+    /// `ct_len == 1568 && wrap_len == 48` — the same length invariant the
+    /// old `Inspect` command used to `bail!` on.
+    pub valid: bool,
+}
+
+/// Stable, structured document describing a [`Header`], for `Inspect --json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct InspectReport {
+    pub suite: String,
+    pub mlkem1024: bool,
+    pub mldsa87: bool,
+    pub x25519: bool,
+    pub chunk_size: u32,
+    pub aead: String,
+    pub kdf: String,
+    pub kdf_salt_hex: Option<String>,
+    pub kdf_salt_version_note: String,
+    pub recipients: Vec<RecipientReport>,
+    pub signer_public_key_len: Option<usize>,
+    pub fin: u8,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Build a structured report of `hdr`'s fields. Every QSFS v2 suite
+/// negotiates ML-KEM-1024, so `mlkem1024` is always `true`; `mldsa87` and
+/// `x25519` reflect whether this particular header actually carries a
+/// signature / hybrid X25519 component.
+pub fn inspect_header(hdr: &Header) -> InspectReport {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    let x25519 = hdr.eph_x25519_pk != [0u8; 32] || hdr.recipients.iter().any(|r| !r.x25519_pub.is_empty());
+    if !x25519 {
+        warnings.push("missing ephemeral X25519 public key (hybrid KEM expected)".to_string());
+    }
+
+    let recipients = hdr
+        .recipients
+        .iter()
+        .map(|r| {
+            let ct_len = r.mlkem_ct.len();
+            let wrap_len = if !r.wrapped_dek.is_empty() { r.wrapped_dek.len() } else { r.wrap.len() };
+            let x25519_len = r.x25519_pub.len();
+            if ct_len != MLKEM1024_CT_LEN {
+                errors.push(format!("recipient '{}': bad ML-KEM-1024 ciphertext length {}", r.label, ct_len));
+            }
+            if wrap_len != WRAPPED_DEK_LEN {
+                errors.push(format!(
+                    "recipient '{}': wrapped DEK must be {} bytes, got {}",
+                    r.label, WRAPPED_DEK_LEN, wrap_len
+                ));
+            }
+            RecipientReport {
+                label: r.label.clone(),
+                ct_len,
+                wrap_len,
+                x25519_len,
+                valid: ct_len == MLKEM1024_CT_LEN && wrap_len == WRAPPED_DEK_LEN,
+            }
+        })
+        .collect();
+
+    let signer_public_key_len = hdr.signature_metadata.as_ref().map(|m| m.public_key.len());
+    if let Some(len) = signer_public_key_len {
+        if len != MLDSA87_PK_LEN {
+            errors.push(format!("invalid ML-DSA-87 public key length: {}", len));
+        }
+    }
+
+    let (kdf_salt_hex, kdf_salt_version_note) = match &hdr.kdf_salt {
+        Some(salt) => (Some(hex_encode(salt)), "v2.1+; salt bound in AAD".to_string()),
+        None => (None, "v2.0; no per-file salt (KEK HKDF is unsalted)".to_string()),
+    };
+
+    InspectReport {
+        suite: hdr.suite.full_descriptor(),
+        mlkem1024: true,
+        mldsa87: !hdr.mldsa_sig.is_empty(),
+        x25519,
+        chunk_size: hdr.chunk_size,
+        aead: hdr.suite.aead().as_str().to_string(),
+        kdf: hdr.suite.kdf().as_str().to_string(),
+        kdf_salt_hex,
+        kdf_salt_version_note,
+        recipients,
+        signer_public_key_len,
+        fin: hdr.fin,
+        errors,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::RecipientEntry;
+
+    fn base_header() -> Header {
+        Header {
+            magic: *b"QSFS2\0",
+            chunk_size: 65536,
+            file_id: [0u8; 8],
+            blake3_of_plain: [0u8; 32],
+            suite: crate::suite::SuiteId::current(),
+            kdf_salt: None,
+            compression: None,
+            recipients: vec![],
+            passphrase_recipients: vec![],
+            eph_x25519_pk: [1u8; 32],
+            mldsa_sig: vec![],
+            ed25519_sig: vec![],
+            signature_metadata: None,
+            co_signatures: vec![],
+            manifest: None,
+            fin: 1,
+        }
+    }
+
+    #[test]
+    fn well_formed_recipient_reports_no_errors() {
+        let mut hdr = base_header();
+        hdr.recipients.push(RecipientEntry {
+            label: "recipient".to_string(),
+            mlkem_ct: vec![0u8; MLKEM1024_CT_LEN],
+            wrap: vec![],
+            wrapped_dek: vec![0u8; WRAPPED_DEK_LEN],
+            wrap_nonce: [0u8; 12],
+            x25519_pk_fpr: [0u8; 8],
+            x25519_pub: vec![0u8; 32],
+            enc: vec![],
+        });
+
+        let report = inspect_header(&hdr);
+        assert!(report.errors.is_empty());
+        assert!(report.recipients[0].valid);
+        assert!(report.x25519);
+    }
+
+    #[test]
+    fn malformed_recipient_is_reported_not_rejected() {
+        let mut hdr = base_header();
+        hdr.recipients.push(RecipientEntry {
+            label: "bad".to_string(),
+            mlkem_ct: vec![0u8; 10],
+            wrap: vec![],
+            wrapped_dek: vec![0u8; 10],
+            wrap_nonce: [0u8; 12],
+            x25519_pk_fpr: [0u8; 8],
+            x25519_pub: vec![],
+            enc: vec![],
+        });
+
+        let report = inspect_header(&hdr);
+        assert_eq!(report.errors.len(), 2);
+        assert!(!report.recipients[0].valid);
+    }
+
+    #[test]
+    fn missing_x25519_produces_a_warning() {
+        let mut hdr = base_header();
+        hdr.eph_x25519_pk = [0u8; 32];
+        let report = inspect_header(&hdr);
+        assert!(!report.x25519);
+        assert_eq!(report.warnings.len(), 1);
+    }
+}
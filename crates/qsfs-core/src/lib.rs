@@ -1,28 +1,55 @@
+//! `seal_bytes`/`open_bytes`/`seal_stream`/`unseal_stream` (see below) are
+//! filesystem-free and generic over `AsyncRead`/`Write`, so they compile for
+//! `wasm32-unknown-unknown` with `getrandom`'s JS backend (chunk5-5). The
+//! path-based convenience wrappers (`seal`, `unseal`, `unseal_path`,
+//! `seal_bundle`, `unseal_bundle`), which touch `tokio::fs`/atomic rename,
+//! are gated behind the `native` feature so they simply don't exist on that
+//! target rather than failing to link. This doesn't extend to the on-disk
+//! trust store: `unseal_stream`'s signer-trust check calls
+//! `signer::default_trustdb_path` (`dirs::home_dir` + `std::fs`) when a
+//! signature is present and `ctx.trust_any_signer` is not set, and its
+//! quorum check calls it whenever `ctx.min_valid_signers > 0`, even with
+//! `ctx.trust_any_signer` set.
+
+pub mod armor;
 pub mod derivation;
+mod cose;
+#[cfg(feature = "hybrid-x25519")]
+pub mod handshake;
 mod header;
+mod hpke;
+pub mod identity;
+pub mod inspect;
 mod pq;
+pub mod rfc8188;
 mod streaming;
-mod security;
+pub mod security;
 pub mod suite;
 pub mod pae;
 pub mod signer;
 pub mod canonical;
+pub mod passphrase;
 
 use anyhow::Result;
-use derivation::{derive_file_nonce_seed, hkdf_expand_keys, ContentEncryptionKey, derive_kek, wrap_dek, unwrap_dek};
-pub use header::{Header, RecipientEntry, SignatureMetadata};
+use derivation::{derive_file_nonce_seed, hkdf_expand_keys, ContentEncryptionKey, KemTranscript, wrap_dek, unwrap_dek};
+pub use header::{Header, ManifestEntry, PassphraseRecipient, RecipientEntry, SignatureMetadata};
+pub use passphrase::Argon2Params;
 use crate::suite::SuiteId;
 use pq::mlkem;
-use tokio::{fs::File, io::AsyncReadExt};
-use base64::{engine::general_purpose, Engine as _};
+use tokio::{fs::File, io::{AsyncRead, AsyncReadExt}};
 use rand::RngCore;
 use std::io::Write;
 use secrecy::ExposeSecret;
 use tempfile::NamedTempFile;
 use std::path::Path;
 use security::{disable_core_dumps, set_secure_permissions};
-pub use signer::{Signer, TrustStore, verify_signature, default_trustdb_path, auto_provision_signer};
+pub use signer::{
+    FileSigner, LocalSigner, RemoteSigner, RevocationEntry, Role, RotationAttestation, Signer,
+    TrustStore, auto_provision_signer, default_trustdb_path, verify_hybrid_signature,
+    verify_rotation_attestation, verify_signature,
+};
 pub use canonical::{CanonicalHeader, SignatureMetadata as CanonicalSignatureMetadata};
+pub use identity::{derive_identity, DerivedIdentity};
 
 #[cfg(feature="pq")]
 use pqcrypto_traits::kem::{SharedSecret as SharedSecretTrait, Ciphertext as CiphertextTrait};
@@ -30,11 +57,42 @@ use pqcrypto_traits::kem::{SharedSecret as SharedSecretTrait, Ciphertext as Ciph
 use pqcrypto_traits::sign::PublicKey as PublicKeyTrait;
 
 pub struct SealRequest<'a> {
-    pub input_path: &'a str,
     pub recipients: Vec<(String, pqcrypto_mlkem::mlkem1024::PublicKey, [u8;32])>,
     pub header_sign_mldsa_sk: Option<pqcrypto_mldsa::mldsa87::SecretKey>,
+    /// The negotiated (KEM, KDF, AEAD) triple for this file (chunk0-1), see
+    /// [`SuiteId`]. Recorded whole in `Header::suite` and dispatched on in
+    /// key derivation/streaming.
+    pub suite: SuiteId,
     pub chunk_size: usize,
-    pub signer: Option<&'a Signer>,
+    pub signer: Option<&'a LocalSigner>,
+    /// Emit the container as an ASCII-armored `QSFS MESSAGE` block instead
+    /// of the raw binary layout (see `armor`).
+    pub armor: bool,
+    /// Sign the header as a COSE_Sign1 envelope (see `cose`) instead of the
+    /// crate's raw hybrid ML-DSA-87 + Ed25519 encoding, for interop with
+    /// generic COSE verifiers. Only takes effect when `signer` is set; the
+    /// Ed25519 half of the hybrid signature is dropped in this mode, since
+    /// a COSE_Sign1 envelope carries a single signature algorithm.
+    pub cose_sign1: bool,
+    /// Offload the per-chunk bulk AEAD to a PKCS#11 HSM key instead of the
+    /// in-memory `aes_k1` (see `security::hsm`, `streaming::ChunkCipher`).
+    /// Only compatible with `AeadId::Aes256Gcm`; `None` uses the software
+    /// path, unchanged from before HSM support existed.
+    pub hsm: Option<(&'a security::hsm::HsmSession, security::hsm::HsmKeyHandle)>,
+    /// Bundle mode (see `seal_bundle`): a signed manifest covering every
+    /// file packed into this container. `None` for an ordinary single-file
+    /// seal; set internally by `seal_bundle` rather than by most callers.
+    pub manifest: Option<Vec<header::ManifestEntry>>,
+    /// Passphrase recipients (chunk2-4): `(label, passphrase, Argon2id cost
+    /// params)`. Each wraps the same DEK as the hybrid KEM recipients
+    /// above, so a file can be sealed to a password alongside or instead
+    /// of a keypair.
+    pub passphrases: Vec<(String, String, passphrase::Argon2Params)>,
+    /// Opt-in pre-encryption compression (chunk2-5, see
+    /// `streaming::encrypt_stream`). Only compress content you know isn't
+    /// secret-dependent alongside attacker-influenced bytes — compression
+    /// ratio can leak information about such content (CRIME/BREACH-style).
+    pub compress: bool,
 }
 
 pub struct UnsealContext<'a> {
@@ -42,18 +100,56 @@ pub struct UnsealContext<'a> {
     pub x25519_sk: Option<[u8;32]>,
     pub allow_unsigned: bool,
     pub trust_any_signer: bool,
+    /// HSM key to unseal with, mirroring [`SealRequest::hsm`]. Must match
+    /// whatever the file was sealed with — the wire format is identical
+    /// either way, so this is purely about where the AEAD runs.
+    pub hsm: Option<(&'a security::hsm::HsmSession, security::hsm::HsmKeyHandle)>,
+    /// Passphrase to try against the header's passphrase recipients (see
+    /// [`SealRequest::passphrases`]), tried only if no hybrid KEM
+    /// recipient matches `mlkem_sk`.
+    pub passphrase: Option<String>,
+    /// Quorum policy (chunk5-2): the number of *distinct* trusted signers
+    /// — counting the primary signature plus any `Header::co_signatures`
+    /// — that must independently verify before the file is accepted. `0`
+    /// (the default) keeps the pre-quorum behavior: the primary signature
+    /// check above is the only gate.
+    pub min_valid_signers: usize,
+}
+
+/// Armor headers describing `hdr`, used for both file- and byte-oriented
+/// armored output so the two call sites can't drift apart.
+fn armor_headers(hdr: &Header) -> Vec<(String, String)> {
+    vec![
+        ("suite".to_string(), hdr.suite.as_str().to_string()),
+        (
+            "signer".to_string(),
+            hdr.signature_metadata
+                .as_ref()
+                .map(|m| m.signer_id.clone())
+                .unwrap_or_default(),
+        ),
+    ]
 }
 
-pub async fn seal(req: SealRequest<'_>, output_path: &str) -> Result<()> {
+/// Core of [`seal`]/[`seal_bytes`]: read plaintext from `reader`, write the
+/// QSFS2 container (header + encrypted stream) to `writer`, and return the
+/// header that was written so callers can label an armored block, etc.
+/// Generic over `AsyncRead`/`Write` so it serves both file paths and
+/// in-memory byte buffers without duplicating the seal logic.
+pub async fn seal_stream(
+    mut req: SealRequest<'_>,
+    mut reader: impl AsyncRead + Unpin,
+    writer: &mut impl Write,
+) -> Result<Header> {
     // Disable core dumps for security
     disable_core_dumps().ok();
-    
+
     // 1) Prepare header (no plaintext fingerprint in clear)
-    
+
     // 2) Generate CEK and wrap for each recipient
     let cek = ContentEncryptionKey::generate()?;
     let mut recipients = Vec::new();
-    
+
     // Ephemeral X25519 key for this file
     #[cfg(feature="hybrid-x25519")]
     let eph_x_sk = {
@@ -67,26 +163,59 @@ pub async fn seal(req: SealRequest<'_>, output_path: &str) -> Result<()> {
     let mut kdf_salt = [0u8; 32];
     rand::rngs::OsRng.fill_bytes(&mut kdf_salt);
 
+    let suite = req.suite;
+
+    // Derived ahead of the recipient loop: the HPKE `info` (file_id + suite)
+    // folded into every recipient's key schedule below.
+    let file_id = derive_file_nonce_seed(cek.expose_secret());
+    let hpke_info = hpke::info_for(&file_id, suite);
+
     for (label, mlkem_pk, recip_x25519_pk_bytes) in req.recipients {
         let (ss, ct) = mlkem::encapsulate(&mlkem_pk);
 
         #[cfg(feature="hybrid-x25519")]
-        let kek = {
+        let (enc, kek) = {
             let recip_x_pk = x25519_dalek::PublicKey::from(recip_x25519_pk_bytes);
             let x_ss = eph_x_sk.diffie_hellman(&recip_x_pk);
-            derive_kek(ss.as_bytes(), x_ss.as_bytes(), Some(&kdf_salt))
+            let transcript = KemTranscript {
+                mlkem_ss: ss.as_bytes(),
+                x25519_ss: x_ss.as_bytes(),
+                mlkem_ct: ct.as_bytes(),
+                eph_x25519_pk: eph_x_pk.as_bytes(),
+                recipient_x25519_pk: &recip_x25519_pk_bytes,
+            };
+            // RFC 9180 HPKE: SetupBaseS(pkR, info) followed by
+            // Export("qsfs-dek-wrap", 32) in place of an ad-hoc HKDF call,
+            // so `enc` and `info` are cryptographically bound into the KEK.
+            let (enc, context) = hpke::setup_base_s(&transcript, Some(&kdf_salt), suite.kdf(), &hpke_info);
+            let kek_bytes = context.export(b"qsfs-dek-wrap", 32);
+            let mut kek = [0u8; 32];
+            kek.copy_from_slice(&kek_bytes);
+            (enc, kek)
         };
 
     #[cfg(not(feature="hybrid-x25519"))]
-    let kek = {
-            // Always KDF the shared secret (even when not hybrid)
-            derive_kek(ss.as_bytes(), &[], Some(&kdf_salt))
+    let (enc, kek) = {
+            // Always KDF the shared secret (even when not hybrid), still
+            // binding the ML-KEM ciphertext into the transcript.
+            let transcript = KemTranscript {
+                mlkem_ss: ss.as_bytes(),
+                x25519_ss: &[],
+                mlkem_ct: ct.as_bytes(),
+                eph_x25519_pk: &[],
+                recipient_x25519_pk: &[],
+            };
+            let (enc, context) = hpke::setup_base_s(&transcript, Some(&kdf_salt), suite.kdf(), &hpke_info);
+            let kek_bytes = context.export(b"qsfs-dek-wrap", 32);
+            let mut kek = [0u8; 32];
+            kek.copy_from_slice(&kek_bytes);
+            (enc, kek)
         };
 
         // Wrap DEK under KEK
         let mut wrap_nonce = [0u8;12];
         rand::rngs::OsRng.fill_bytes(&mut wrap_nonce);
-        let wrapped_dek = wrap_dek(&kek, &wrap_nonce, cek.expose_secret())?;
+        let wrapped_dek = wrap_dek(&kek, &wrap_nonce, cek.expose_secret(), suite.aead())?;
 
         // Recipient fingerprint
         let x25519_pk_fpr = {
@@ -102,22 +231,40 @@ pub async fn seal(req: SealRequest<'_>, output_path: &str) -> Result<()> {
             wrap_nonce,
             x25519_pk_fpr,
             x25519_pub: recip_x25519_pk_bytes.to_vec(),
+            enc,
         });
     }
 
-    // 4) Derive keys and file-id from CEK with enhanced domain separation
+    // 3b) Wrap the DEK for each passphrase recipient (chunk2-4): KEK is
+    // Argon2id(passphrase, kdf_salt) rather than a KEM shared secret, but
+    // wrapped with the same wrap_dek/unwrap_dek path as every other
+    // recipient kind.
+    let mut passphrase_recipients = Vec::with_capacity(req.passphrases.len());
+    for (label, phrase, params) in &req.passphrases {
+        passphrase_recipients.push(passphrase::wrap_for_passphrase(
+            label,
+            phrase,
+            &kdf_salt,
+            cek.expose_secret(),
+            *params,
+            suite.aead(),
+        )?);
+    }
+
+    // 4) Derive stream keys from CEK with enhanced domain separation
     let confirm = b"qsfs_confirm_v2";
     let keys = hkdf_expand_keys(cek.expose_secret(), Some(confirm));
-    let file_id = derive_file_nonce_seed(cek.expose_secret());
-    
+
     let mut hdr = header::Header {
         magic: *b"QSFS2\0",
         chunk_size: req.chunk_size as u32,
         file_id,
         blake3_of_plain: [0u8;32],
-        suite: SuiteId::current(),
+        suite,
         kdf_salt: Some(kdf_salt),
+        compression: if req.compress { Some(suite::CompressionId::Zstd) } else { None },
         recipients,
+        passphrase_recipients,
         #[cfg(feature="hybrid-x25519")]
         eph_x25519_pk: *eph_x_pk.as_bytes(),
         #[cfg(not(feature="hybrid-x25519"))]
@@ -125,110 +272,180 @@ pub async fn seal(req: SealRequest<'_>, output_path: &str) -> Result<()> {
         mldsa_sig: vec![],
         ed25519_sig: vec![],
         signature_metadata: None,
+        co_signatures: Vec::new(),
+        manifest: req.manifest.take(),
         fin: 1,
     };
-    
-    // Sign header with ML-DSA-87 if signer is provided
+
+    // Sign header with a hybrid ML-DSA-87 + Ed25519 signature if a signer is
+    // provided, so verification requires breaking both a post-quantum and a
+    // classical signature scheme at once (defense in depth).
     if let Some(signer) = req.signer {
         let canonical_bytes = CanonicalHeader::serialize(&hdr)?;
-        let signature = signer.sign(&canonical_bytes)?;
-        
-        let sig_metadata = CanonicalSignatureMetadata::new(
-            signer.id_hex(),
-            signer.pk.as_bytes().to_vec(),
-            signature.clone(),
-        );
-        
-        hdr.mldsa_sig = signature;
-        hdr.signature_metadata = Some(SignatureMetadata {
-            signer_id: sig_metadata.signer_id,
-            algorithm: sig_metadata.algorithm,
-            public_key: sig_metadata.public_key,
-        });
+
+        if req.cose_sign1 {
+            hdr.mldsa_sig = cose::sign(signer, &canonical_bytes)?;
+            hdr.ed25519_sig = vec![];
+            hdr.signature_metadata = Some(SignatureMetadata {
+                signer_id: signer.id_hex(),
+                algorithm: "cose-sign1+ml-dsa-87".to_string(),
+                public_key: signer.pk.as_bytes().to_vec(),
+                ed25519_public_key: vec![],
+            });
+        } else {
+            let mldsa_sig = signer.sign(&canonical_bytes)?;
+            let ed25519_sig = signer.sign_ed25519(&canonical_bytes);
+
+            hdr.mldsa_sig = mldsa_sig;
+            hdr.ed25519_sig = ed25519_sig;
+            hdr.signature_metadata = Some(SignatureMetadata {
+                signer_id: signer.id_hex(),
+                algorithm: "ml-dsa-87+ed25519".to_string(),
+                public_key: signer.pk.as_bytes().to_vec(),
+                ed25519_public_key: signer.ed25519_pk.as_bytes().to_vec(),
+            });
+        }
     }
-    
-    // 5) Atomic write: use temporary file with secure permissions
-    let output_dir = Path::new(output_path).parent().unwrap_or(Path::new("."));
-    let mut temp_file = NamedTempFile::new_in(output_dir)?;
-    
-    // Set secure permissions on temporary file
-    set_secure_permissions(temp_file.path()).ok();
-    
+
     let hdr_bytes = postcard::to_allocvec(&hdr)?;
 
     // Write header length + header + encrypted stream
-    temp_file.write_all(&(hdr_bytes.len() as u32).to_be_bytes())?;
-    temp_file.write_all(&hdr_bytes)?;
+    writer.write_all(&(hdr_bytes.len() as u32).to_be_bytes())?;
+    writer.write_all(&hdr_bytes)?;
 
     let aad = hdr.aead_aad();
+    let chunk_cipher = match req.hsm {
+        Some((session, key)) => streaming::ChunkCipher::Hsm { session, key },
+        None => streaming::ChunkCipher::Software,
+    };
     streaming::encrypt_stream(
-        req.input_path,
-        temp_file.as_file_mut(),
+        &mut reader,
+        writer,
         req.chunk_size,
         file_id,
         &aad,
         keys.aes_k1.expose_secret(),
-        None,
+        hdr.suite.aead(),
+        chunk_cipher,
+        hdr.compression.is_some(),
     ).await?;
 
-    // Ensure data is written to disk before atomic rename
-    temp_file.as_file_mut().sync_all()?;
-    
-    // Atomic rename
-    temp_file.persist(output_path)?;
-    
-    Ok(())
+    Ok(hdr)
 }
 
-pub async fn unseal(mut input: File, output_path: &str, ctx: UnsealContext<'_>) -> Result<()> {
+/// Attach a quorum co-signature (chunk5-2) from `signer` to an already-
+/// sealed container's header, returning the updated container bytes. The
+/// encrypted stream is never touched — only the header's length prefix and
+/// body change — which is safe because `CanonicalHeader::serialize` never
+/// includes any signature-bearing field, so appending a co-signature can't
+/// invalidate the primary signature or any other co-signature already
+/// present. If `signer` already has an entry in `Header::co_signatures`, it
+/// is replaced rather than duplicated.
+pub fn attach_co_signature(container: &[u8], signer: &LocalSigner) -> Result<Vec<u8>> {
+    if container.len() < 4 {
+        return Err(anyhow::anyhow!("container too short to contain a header"));
+    }
+    let hdr_len = u32::from_be_bytes(container[0..4].try_into().unwrap()) as usize;
+    if container.len() < 4 + hdr_len {
+        return Err(anyhow::anyhow!("container truncated: header length exceeds available bytes"));
+    }
+    let hdr_bytes = &container[4..4 + hdr_len];
+    let mut hdr: Header = postcard::from_bytes(hdr_bytes)?;
+    let stream_tail = &container[4 + hdr_len..];
+
+    let canonical_bytes = CanonicalHeader::serialize(&hdr)?;
+    let co_sig = header::CoSignature {
+        signer_id: signer.id_hex(),
+        algorithm: "ml-dsa-87+ed25519".to_string(),
+        public_key: signer.pk.as_bytes().to_vec(),
+        ed25519_public_key: signer.ed25519_pk.as_bytes().to_vec(),
+        signature: signer.sign(&canonical_bytes)?,
+        ed25519_signature: signer.sign_ed25519(&canonical_bytes),
+    };
+    hdr.co_signatures.retain(|c| c.signer_id != co_sig.signer_id);
+    hdr.co_signatures.push(co_sig);
+
+    let new_hdr_bytes = postcard::to_allocvec(&hdr)?;
+    let mut out = Vec::with_capacity(4 + new_hdr_bytes.len() + stream_tail.len());
+    out.extend_from_slice(&(new_hdr_bytes.len() as u32).to_be_bytes());
+    out.extend_from_slice(&new_hdr_bytes);
+    out.extend_from_slice(stream_tail);
+    Ok(out)
+}
+
+/// Core of [`unseal`]/[`open_bytes`]: parse the QSFS2 container from
+/// `reader`, verify its signature and recipient, write the recovered
+/// plaintext to `writer`, and return the parsed header (e.g. for
+/// `unseal_bundle` to read `Header::manifest` off of). Generic over
+/// `AsyncRead`/`Write` so it serves both file paths and in-memory byte
+/// buffers without duplicating the unseal logic.
+pub async fn unseal_stream(
+    mut reader: impl AsyncRead + Unpin,
+    writer: &mut impl Write,
+    ctx: UnsealContext<'_>,
+) -> Result<Header> {
     // Disable core dumps for security
     disable_core_dumps().ok();
-    
+
     // 1) Read header length and header
     let mut len_buf = [0u8; 4];
-    input.read_exact(&mut len_buf).await?;
+    reader.read_exact(&mut len_buf).await?;
     let hdr_len = u32::from_be_bytes(len_buf) as usize;
-    
+
     if hdr_len > 1024 * 1024 {
         return Err(anyhow::anyhow!("Header too large: {}", hdr_len));
     }
-    
+
     let mut hdr_buf = vec![0u8; hdr_len];
-    input.read_exact(&mut hdr_buf).await?;
+    reader.read_exact(&mut hdr_buf).await?;
     let hdr: Header = postcard::from_bytes(&hdr_buf)?;
     // Enforce magic/version
     if hdr.magic != *b"QSFS2\0" {
         return Err(anyhow::anyhow!("Unrecognized file format (bad magic)"));
     }
-    
+
     // 2) Verify signature if present (default behavior)
     if !hdr.mldsa_sig.is_empty() {
-        // Signature is present - verify it
+        // Signature is present - verify the hybrid ML-DSA-87 + Ed25519 pair
         let canonical_bytes = CanonicalHeader::serialize(&hdr)?;
-        
+
         if let Some(sig_metadata) = &hdr.signature_metadata {
-            let public_key_bytes = general_purpose::STANDARD
-                .decode(&sig_metadata.public_key)
-                .map_err(|e| anyhow::anyhow!("Invalid public key base64: {}", e))?;
-            
-            // Verify signature
-            let signature_valid = verify_signature(&canonical_bytes, &hdr.mldsa_sig, &public_key_bytes)?;
+            let signature_valid = if sig_metadata.algorithm == "cose-sign1+ml-dsa-87" {
+                // COSE_Sign1 envelope (see `cose`): a single ML-DSA-87
+                // signature, no Ed25519 half to check.
+                cose::verify(&hdr.mldsa_sig, &canonical_bytes, &sig_metadata.public_key)?
+            } else {
+                if hdr.ed25519_sig.is_empty() {
+                    return Err(anyhow::anyhow!("❌ Ed25519 half of the hybrid signature is missing"));
+                }
+
+                verify_hybrid_signature(
+                    &canonical_bytes,
+                    &hdr.mldsa_sig,
+                    &sig_metadata.public_key,
+                    &hdr.ed25519_sig,
+                    &sig_metadata.ed25519_public_key,
+                )?
+            };
             if !signature_valid {
-                return Err(anyhow::anyhow!("❌ ML-DSA-87 signature verification failed"));
+                return Err(anyhow::anyhow!("❌ header signature verification failed"));
             }
-            
-            // Check trust store unless --trust-any-signer is specified
+
+            // Check trust store unless --trust-any-signer is specified. A
+            // recipient label matching a configured delegation (chunk5-3)
+            // defers this check to that label's delegated sub-store.
             if !ctx.trust_any_signer {
                 let trust_store = TrustStore::load_from_file(default_trustdb_path()?)?;
-                if !trust_store.is_trusted(&sig_metadata.signer_id) {
+                let labels: Vec<&str> = hdr.recipients.iter().map(|r| r.label.as_str()).collect();
+                if !trust_store.is_trusted_for_labels(&sig_metadata.signer_id, &labels)? {
                     return Err(anyhow::anyhow!(
-                        "❌ Signer not trusted: {} (use 'qsfs trust add' or --trust-any-signer)", 
+                        "❌ Signer not trusted: {} (use 'qsfs trust add' or --trust-any-signer)",
                         sig_metadata.signer_id
                     ));
                 }
             }
-            
-            eprintln!("✅ ML-DSA-87 signature verified: {}", sig_metadata.signer_id);
+
+            eprintln!("✅ {} signature verified: {}", sig_metadata.algorithm, sig_metadata.signer_id);
         } else {
             return Err(anyhow::anyhow!("❌ Signature present but metadata missing"));
         }
@@ -242,20 +459,57 @@ pub async fn unseal(mut input: File, output_path: &str, ctx: UnsealContext<'_>)
         eprintln!("⚠️  Processing unsigned file (--allow-unsigned specified)");
     }
 
+    // 2b) Quorum policy (chunk5-2): count distinct trusted signers across
+    // the primary signature and any attached co-signatures, verifying
+    // every one rather than stopping at the first bad entry, so a
+    // malicious partial set can't fool the threshold.
+    if ctx.min_valid_signers > 0 {
+        let canonical_bytes = CanonicalHeader::serialize(&hdr)?;
+        let trust_store = TrustStore::load_from_file(default_trustdb_path()?)?;
+        let trusted_signers = signer::trusted_signer_set(&hdr, &canonical_bytes, &trust_store)?;
+
+        if trusted_signers.len() < ctx.min_valid_signers {
+            return Err(anyhow::anyhow!(
+                "❌ quorum not met: {} of {} required trusted signatures present",
+                trusted_signers.len(),
+                ctx.min_valid_signers
+            ));
+        }
+    }
+
     // 3) Try to decrypt CEK with our key (verifiable decapsulation)
+    let hpke_info = hpke::info_for(&hdr.file_id, hdr.suite);
     let mut cek_bytes = None;
     for rec in &hdr.recipients {
         if let Ok(ct) = pqcrypto_mlkem::mlkem1024::Ciphertext::from_bytes(&rec.mlkem_ct) {
-            let ss = mlkem::decapsulate(&ct, ctx.mlkem_sk);
+            // Reject a malformed/malicious `mlkem_ct` before trusting
+            // anything derived from it (chunk2-6), instead of relying on
+            // the AEAD wrap check further downstream to fail closed.
+            let ss = match mlkem::verify_decapsulate(&ct, ctx.mlkem_sk) {
+                Ok(ss) => ss,
+                Err(_) => continue,
+            };
             #[cfg(feature="hybrid-x25519")]
             {
                 if let Some(xsk) = ctx.x25519_sk {
                     let recip_x_sk = x25519_dalek::StaticSecret::from(xsk);
                     let eph_x_pk = x25519_dalek::PublicKey::from(hdr.eph_x25519_pk);
                     let x_ss = recip_x_sk.diffie_hellman(&eph_x_pk);
-                    let kek = derive_kek(ss.as_bytes(), x_ss.as_bytes(), hdr.kdf_salt.as_ref().map(|s| s.as_slice()));
+                    let transcript = KemTranscript {
+                        mlkem_ss: ss.as_bytes(),
+                        x25519_ss: x_ss.as_bytes(),
+                        mlkem_ct: &rec.mlkem_ct,
+                        eph_x25519_pk: &hdr.eph_x25519_pk,
+                        recipient_x25519_pk: &rec.x25519_pub,
+                    };
+                    // RFC 9180 HPKE: SetupBaseR(enc, skR, info) followed by
+                    // the same Export call the sender made.
+                    let context = hpke::setup_base_r(&transcript, hdr.kdf_salt.as_ref().map(|s| s.as_slice()), hdr.suite.kdf(), &hpke_info);
+                    let kek_bytes = context.export(b"qsfs-dek-wrap", 32);
+                    let mut kek = [0u8; 32];
+                    kek.copy_from_slice(&kek_bytes);
                     if rec.wrapped_dek.len() == 48 {
-                        if let Ok(cek) = unwrap_dek(&kek, &rec.wrap_nonce, &rec.wrapped_dek) {
+                        if let Ok(cek) = unwrap_dek(&kek, &rec.wrap_nonce, &rec.wrapped_dek, hdr.suite.aead()) {
                             cek_bytes = Some((cek, b"qsfs_confirm_v2".to_vec()));
                             break;
                         }
@@ -267,9 +521,19 @@ pub async fn unseal(mut input: File, output_path: &str, ctx: UnsealContext<'_>)
             #[cfg(not(feature="hybrid-x25519"))]
             {
                 // Non-hybrid: use KEK derived from ML-KEM SS and unwrap via AES-GCM
-                let kek = derive_kek(ss.as_bytes(), &[], hdr.kdf_salt.as_ref().map(|s| s.as_slice()));
+                let transcript = KemTranscript {
+                    mlkem_ss: ss.as_bytes(),
+                    x25519_ss: &[],
+                    mlkem_ct: &rec.mlkem_ct,
+                    eph_x25519_pk: &[],
+                    recipient_x25519_pk: &[],
+                };
+                let context = hpke::setup_base_r(&transcript, hdr.kdf_salt.as_ref().map(|s| s.as_slice()), hdr.suite.kdf(), &hpke_info);
+                let kek_bytes = context.export(b"qsfs-dek-wrap", 32);
+                let mut kek = [0u8; 32];
+                kek.copy_from_slice(&kek_bytes);
                 if rec.wrapped_dek.len() == 48 {
-                    if let Ok(cek) = unwrap_dek(&kek, &rec.wrap_nonce, &rec.wrapped_dek) {
+                    if let Ok(cek) = unwrap_dek(&kek, &rec.wrap_nonce, &rec.wrapped_dek, hdr.suite.aead()) {
                         cek_bytes = Some((cek, b"qsfs_confirm_v2".to_vec()));
                         break;
                     }
@@ -277,31 +541,492 @@ pub async fn unseal(mut input: File, output_path: &str, ctx: UnsealContext<'_>)
             }
         }
     }
-    
+
+    // 3b) Fall back to a passphrase recipient (chunk2-4) if no KEM
+    // recipient matched and the caller supplied one.
+    if cek_bytes.is_none() {
+        if let (Some(passphrase), Some(salt)) = (&ctx.passphrase, &hdr.kdf_salt) {
+            for rec in &hdr.passphrase_recipients {
+                if let Some(cek) = passphrase::unwrap_with_passphrase(rec, passphrase, salt, hdr.suite.aead()) {
+                    cek_bytes = Some((cek, b"qsfs_confirm_v2".to_vec()));
+                    break;
+                }
+            }
+        }
+    }
+
     let (cek, confirm) = cek_bytes.ok_or_else(|| anyhow::anyhow!("No matching recipient key"))?;
-    
+
     // 4) Derive keys from CEK
     let keys = hkdf_expand_keys(&cek, Some(&confirm));
-    
-    // 5) Atomic write: use temporary file with secure permissions
-    let output_dir = Path::new(output_path).parent().unwrap_or(Path::new("."));
-    let mut temp_file = NamedTempFile::new_in(output_dir)?;
-    
-    // Set secure permissions on temporary file
-    set_secure_permissions(temp_file.path()).ok();
-    
+
     let aad = hdr.aead_aad();
-    let mut rest = input;
-    streaming::decrypt_stream(&mut rest, temp_file.as_file_mut(), hdr.file_id, &aad,
+    let chunk_cipher = match ctx.hsm {
+        Some((session, key)) => streaming::ChunkCipher::Hsm { session, key },
+        None => streaming::ChunkCipher::Software,
+    };
+    streaming::decrypt_stream(&mut reader, writer, hdr.file_id, &aad,
         keys.aes_k1.expose_secret(),
-        None,
+        hdr.suite.aead(),
+        chunk_cipher,
+        hdr.compression.is_some(),
     ).await?;
 
+    Ok(hdr)
+}
+
+/// Seal the file at `input_path`, writing the QSFS2 container to
+/// `output_path` via an atomic rename.
+#[cfg(feature = "native")]
+pub async fn seal(req: SealRequest<'_>, input_path: &str, output_path: &str) -> Result<()> {
+    let do_armor = req.armor;
+    let reader = File::open(input_path).await?;
+
+    let output_dir = Path::new(output_path).parent().unwrap_or(Path::new("."));
+    let mut temp_file = NamedTempFile::new_in(output_dir)?;
+    set_secure_permissions(temp_file.path()).ok();
+
+    let hdr = seal_stream(req, reader, temp_file.as_file_mut()).await?;
+
+    // Ensure data is written to disk before atomic rename
+    temp_file.as_file_mut().sync_all()?;
+
+    if do_armor {
+        let binary = std::fs::read(temp_file.path())?;
+        let armored = armor::armor(&binary, &armor_headers(&hdr));
+        let mut armored_file = NamedTempFile::new_in(output_dir)?;
+        set_secure_permissions(armored_file.path()).ok();
+        armored_file.write_all(armored.as_bytes())?;
+        armored_file.as_file_mut().sync_all()?;
+        armored_file.persist(output_path)?;
+    } else {
+        // Atomic rename
+        temp_file.persist(output_path)?;
+    }
+
+    Ok(())
+}
+
+/// Seal `plaintext` entirely in memory, returning the QSFS2 container bytes
+/// (or an armored text block, if `req.armor` is set) without touching disk.
+pub async fn seal_bytes(req: SealRequest<'_>, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let do_armor = req.armor;
+    let mut out = Vec::new();
+    let hdr = seal_stream(req, plaintext, &mut out).await?;
+
+    if do_armor {
+        Ok(armor::armor(&out, &armor_headers(&hdr)).into_bytes())
+    } else {
+        Ok(out)
+    }
+}
+
+/// Unseal an already-opened binary QSFS2 container, writing the recovered
+/// plaintext to `output_path` via an atomic rename.
+#[cfg(feature = "native")]
+pub async fn unseal(input: File, output_path: &str, ctx: UnsealContext<'_>) -> Result<()> {
+    let output_dir = Path::new(output_path).parent().unwrap_or(Path::new("."));
+    let mut temp_file = NamedTempFile::new_in(output_dir)?;
+    set_secure_permissions(temp_file.path()).ok();
+
+    unseal_stream(input, temp_file.as_file_mut(), ctx).await?;
+
     // Ensure data is written to disk before atomic rename
     temp_file.as_file_mut().sync_all()?;
-    
+
+    // Atomic rename
+    temp_file.persist(output_path)?;
+
+    Ok(())
+}
+
+/// Like [`unseal`], but transparently de-armors `input_path` first if it is
+/// an ASCII-armored `QSFS MESSAGE` block rather than the raw binary
+/// container, so callers never need to know which form a file is in.
+#[cfg(feature = "native")]
+pub async fn unseal_path(input_path: &str, output_path: &str, ctx: UnsealContext<'_>) -> Result<()> {
+    let raw = tokio::fs::read(input_path).await?;
+
+    let output_dir = Path::new(output_path).parent().unwrap_or(Path::new("."));
+    let mut temp_file = NamedTempFile::new_in(output_dir)?;
+    set_secure_permissions(temp_file.path()).ok();
+
+    if armor::is_armored(&raw) {
+        let text = String::from_utf8(raw).map_err(|_| anyhow::anyhow!("armored input is not valid UTF-8"))?;
+        let binary = armor::dearmor(&text)?;
+        unseal_stream(&binary[..], temp_file.as_file_mut(), ctx).await?;
+    } else {
+        unseal_stream(&raw[..], temp_file.as_file_mut(), ctx).await?;
+    }
+
+    // Ensure data is written to disk before atomic rename
+    temp_file.as_file_mut().sync_all()?;
+
     // Atomic rename
     temp_file.persist(output_path)?;
-    
+
     Ok(())
 }
+
+/// Unseal `data` (binary or armored) entirely in memory, returning the
+/// recovered plaintext without touching disk.
+pub async fn open_bytes(data: &[u8], ctx: UnsealContext<'_>) -> Result<Vec<u8>> {
+    let binary = if armor::is_armored(data) {
+        let text = std::str::from_utf8(data)
+            .map_err(|_| anyhow::anyhow!("armored input is not valid UTF-8"))?;
+        armor::dearmor(text)?
+    } else {
+        data.to_vec()
+    };
+
+    let mut out = Vec::new();
+    unseal_stream(&binary[..], &mut out, ctx).await?;
+    Ok(out)
+}
+
+/// Seal many files into a single bundle container: `files` is a list of
+/// `(relative_path, source path)` pairs, whose plaintext bytes are packed
+/// into one encrypted stream in the given order and covered by a signed
+/// manifest (`Header::manifest`) of each file's length and BLAKE3 digest.
+/// `req.manifest` is computed here and overwritten; callers don't set it.
+#[cfg(feature = "native")]
+pub async fn seal_bundle(
+    mut req: SealRequest<'_>,
+    files: &[(String, &str)],
+    output_path: &str,
+) -> Result<()> {
+    let mut manifest = Vec::with_capacity(files.len());
+    for (relative_path, path) in files {
+        let mut f = File::open(path).await?;
+        let mut hasher = blake3::Hasher::new();
+        let mut buf = vec![0u8; 65536];
+        let mut length = 0u64;
+        loop {
+            let n = f.read(&mut buf).await?;
+            if n == 0 { break; }
+            hasher.update(&buf[..n]);
+            length += n as u64;
+        }
+        manifest.push(header::ManifestEntry {
+            relative_path: relative_path.clone(),
+            length,
+            blake3_digest: *hasher.finalize().as_bytes(),
+        });
+    }
+    req.manifest = Some(manifest);
+    let do_armor = req.armor;
+
+    let output_dir = Path::new(output_path).parent().unwrap_or(Path::new("."));
+    let mut temp_file = NamedTempFile::new_in(output_dir)?;
+    set_secure_permissions(temp_file.path()).ok();
+
+    // Chain every file's bytes together, in manifest order, into one
+    // plaintext stream -- `seal_stream` doesn't need to know it's sealing a
+    // bundle rather than a single file.
+    let mut reader: Box<dyn AsyncRead + Unpin> = Box::new(tokio::io::empty());
+    for (_, path) in files {
+        reader = Box::new(reader.chain(File::open(path).await?));
+    }
+
+    let hdr = seal_stream(req, reader, temp_file.as_file_mut()).await?;
+    temp_file.as_file_mut().sync_all()?;
+
+    if do_armor {
+        let binary = std::fs::read(temp_file.path())?;
+        let armored = armor::armor(&binary, &armor_headers(&hdr));
+        let mut armored_file = NamedTempFile::new_in(output_dir)?;
+        set_secure_permissions(armored_file.path()).ok();
+        armored_file.write_all(armored.as_bytes())?;
+        armored_file.as_file_mut().sync_all()?;
+        armored_file.persist(output_path)?;
+    } else {
+        temp_file.persist(output_path)?;
+    }
+
+    Ok(())
+}
+
+/// Unseal a bundle container written by [`seal_bundle`]: `unseal_stream`
+/// verifies the header signature (which covers `Header::manifest`) as
+/// usual, then every extracted file is checked against its manifest
+/// `blake3_digest` *before* any file is written to disk. If any digest
+/// mismatches, the whole bundle is rejected and nothing is committed.
+#[cfg(feature = "native")]
+pub async fn unseal_bundle(input_path: &str, output_dir: &str, ctx: UnsealContext<'_>) -> Result<()> {
+    let raw = tokio::fs::read(input_path).await?;
+    let binary = if armor::is_armored(&raw) {
+        let text = String::from_utf8(raw).map_err(|_| anyhow::anyhow!("armored input is not valid UTF-8"))?;
+        armor::dearmor(&text)?
+    } else {
+        raw
+    };
+
+    let mut plaintext = Vec::new();
+    let hdr = unseal_stream(&binary[..], &mut plaintext, ctx).await?;
+    let manifest = hdr.manifest.ok_or_else(|| anyhow::anyhow!("container has no bundle manifest"))?;
+
+    // Verify every entry before committing any of them to disk -- a bundle
+    // is all-or-nothing.
+    let mut offset = 0usize;
+    let mut files = Vec::with_capacity(manifest.len());
+    for entry in &manifest {
+        let len = entry.length as usize;
+        let end = offset.checked_add(len).ok_or_else(|| anyhow::anyhow!("manifest length overflow"))?;
+        let data = plaintext
+            .get(offset..end)
+            .ok_or_else(|| anyhow::anyhow!("manifest length exceeds decrypted stream"))?;
+        if blake3::hash(data).as_bytes() != &entry.blake3_digest {
+            return Err(anyhow::anyhow!("❌ bundle entry failed integrity check: {}", entry.relative_path));
+        }
+        files.push((entry.relative_path.as_str(), data));
+        offset = end;
+    }
+    if offset != plaintext.len() {
+        return Err(anyhow::anyhow!("bundle manifest does not cover the entire decrypted stream"));
+    }
+
+    for (relative_path, data) in files {
+        let dest = bundle_entry_path(output_dir, relative_path)?;
+        let parent = dest.parent().unwrap_or(Path::new("."));
+        tokio::fs::create_dir_all(parent).await?;
+
+        let mut temp_file = NamedTempFile::new_in(parent)?;
+        set_secure_permissions(temp_file.path()).ok();
+        temp_file.write_all(data)?;
+        temp_file.as_file_mut().sync_all()?;
+        temp_file.persist(&dest)?;
+    }
+
+    Ok(())
+}
+
+/// Resolve a manifest `relative_path` against `output_dir`, rejecting any
+/// path that would escape it. A bundle is untrusted input until its
+/// manifest digest has been checked, and even then must not be able to
+/// write outside the requested directory.
+fn bundle_entry_path(output_dir: &str, relative_path: &str) -> Result<std::path::PathBuf> {
+    let rel = Path::new(relative_path);
+    if rel.is_absolute() || rel.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+        return Err(anyhow::anyhow!("bundle entry has an unsafe path: {}", relative_path));
+    }
+    Ok(Path::new(output_dir).join(rel))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose, Engine as _};
+    use signer::TrustEntry;
+
+    /// `seal_bytes`/`open_bytes` never touch `tokio::fs` or `NamedTempFile` —
+    /// this exercises the whole round trip with no filesystem involved, the
+    /// property that makes them usable on targets (e.g. `wasm32`) where
+    /// `seal`/`unseal`'s path-based, atomic-rename wrappers are unavailable.
+    #[tokio::test]
+    async fn seal_bytes_and_open_bytes_round_trip_in_memory() {
+        let (mlkem_pk, mlkem_sk) = pq::mlkem::keypair();
+        // X25519 public key matching the `x25519_sk: Some([0u8; 32])` used to open.
+        let x25519_pk = *x25519_dalek::PublicKey::from(&x25519_dalek::StaticSecret::from([0u8; 32])).as_bytes();
+        let req = SealRequest {
+            recipients: vec![("recipient".to_string(), mlkem_pk, x25519_pk)],
+            header_sign_mldsa_sk: None,
+            suite: SuiteId::current(),
+            chunk_size: 65536,
+            signer: None,
+            armor: false,
+            cose_sign1: false,
+            hsm: None,
+            manifest: None,
+            passphrases: vec![],
+            compress: false,
+        };
+
+        let plaintext = b"no filesystem involved".to_vec();
+        let sealed = seal_bytes(req, &plaintext).await.unwrap();
+
+        let ctx = UnsealContext {
+            mlkem_sk: &mlkem_sk,
+            x25519_sk: Some([0u8; 32]),
+            allow_unsigned: true,
+            trust_any_signer: false,
+            hsm: None,
+            passphrase: None,
+            min_valid_signers: 0,
+        };
+        let opened = open_bytes(&sealed, ctx).await.unwrap();
+        assert_eq!(opened, plaintext);
+    }
+
+    /// A file sealed only to a passphrase (no KEM recipients at all) must
+    /// open with the matching passphrase and be rejected for a wrong one.
+    #[tokio::test]
+    async fn passphrase_only_seal_round_trips() {
+        let (_mlkem_pk, mlkem_sk) = pq::mlkem::keypair();
+        let req = SealRequest {
+            recipients: vec![],
+            header_sign_mldsa_sk: None,
+            suite: SuiteId::current(),
+            chunk_size: 65536,
+            signer: None,
+            armor: false,
+            cose_sign1: false,
+            hsm: None,
+            manifest: None,
+            passphrases: vec![(
+                "backup".to_string(),
+                "correct horse battery staple".to_string(),
+                passphrase::Argon2Params { mem_kib: 8 * 1024, time_cost: 1, parallelism: 1 },
+            )],
+            compress: false,
+        };
+
+        let plaintext = b"sealed to a password, not a keypair".to_vec();
+        let sealed = seal_bytes(req, &plaintext).await.unwrap();
+
+        let ctx = UnsealContext {
+            mlkem_sk: &mlkem_sk,
+            x25519_sk: Some([0u8; 32]),
+            allow_unsigned: true,
+            trust_any_signer: false,
+            hsm: None,
+            passphrase: Some("correct horse battery staple".to_string()),
+            min_valid_signers: 0,
+        };
+        let opened = open_bytes(&sealed, ctx).await.unwrap();
+        assert_eq!(opened, plaintext);
+
+        let wrong_ctx = UnsealContext {
+            mlkem_sk: &mlkem_sk,
+            x25519_sk: Some([0u8; 32]),
+            allow_unsigned: true,
+            trust_any_signer: false,
+            hsm: None,
+            passphrase: Some("wrong passphrase".to_string()),
+            min_valid_signers: 0,
+        };
+        assert!(open_bytes(&sealed, wrong_ctx).await.is_err());
+    }
+
+    /// `compress: true` (chunk2-5) must round-trip and the resulting
+    /// container must actually be smaller for compressible plaintext.
+    #[tokio::test]
+    async fn compressed_seal_round_trips_and_shrinks() {
+        let (mlkem_pk1, _mlkem_sk1) = pq::mlkem::keypair();
+        let (mlkem_pk2, mlkem_sk2) = pq::mlkem::keypair();
+        // X25519 public key matching the `x25519_sk: Some([0u8; 32])` used to open.
+        let x25519_pk = *x25519_dalek::PublicKey::from(&x25519_dalek::StaticSecret::from([0u8; 32])).as_bytes();
+        let plaintext = "the quick brown fox jumps over the lazy dog. ".repeat(200).into_bytes();
+
+        let uncompressed_req = SealRequest {
+            recipients: vec![("recipient".to_string(), mlkem_pk1, x25519_pk)],
+            header_sign_mldsa_sk: None,
+            suite: SuiteId::current(),
+            chunk_size: 65536,
+            signer: None,
+            armor: false,
+            cose_sign1: false,
+            hsm: None,
+            manifest: None,
+            passphrases: vec![],
+            compress: false,
+        };
+        let uncompressed = seal_bytes(uncompressed_req, &plaintext).await.unwrap();
+
+        let compressed_req = SealRequest {
+            recipients: vec![("recipient".to_string(), mlkem_pk2, x25519_pk)],
+            header_sign_mldsa_sk: None,
+            suite: SuiteId::current(),
+            chunk_size: 65536,
+            signer: None,
+            armor: false,
+            cose_sign1: false,
+            hsm: None,
+            manifest: None,
+            passphrases: vec![],
+            compress: true,
+        };
+        let compressed = seal_bytes(compressed_req, &plaintext).await.unwrap();
+        assert!(compressed.len() < uncompressed.len(), "compressible plaintext should seal smaller with compress: true");
+
+        let ctx = UnsealContext {
+            mlkem_sk: &mlkem_sk2,
+            x25519_sk: Some([0u8; 32]),
+            allow_unsigned: true,
+            trust_any_signer: false,
+            hsm: None,
+            passphrase: None,
+            min_valid_signers: 0,
+        };
+        let opened = open_bytes(&compressed, ctx).await.unwrap();
+        assert_eq!(opened, plaintext);
+    }
+
+    /// Quorum co-signing (chunk5-2): a file sealed and signed by one signer,
+    /// then co-signed by a second via `attach_co_signature`, must count
+    /// both as distinct trusted signers once trusted, and must drop back
+    /// below quorum once one of them is revoked — exercised against an
+    /// in-memory `TrustStore` (via `signer::trusted_signer_set`) rather
+    /// than the real on-disk trust database.
+    #[tokio::test]
+    async fn quorum_requires_distinct_trusted_signers() {
+        let (mlkem_pk, _mlkem_sk) = pq::mlkem::keypair();
+        let primary = LocalSigner::generate();
+        let co_signer = LocalSigner::generate();
+
+        let req = SealRequest {
+            recipients: vec![("recipient".to_string(), mlkem_pk, [0u8; 32])],
+            header_sign_mldsa_sk: None,
+            suite: SuiteId::current(),
+            chunk_size: 65536,
+            signer: Some(&primary),
+            armor: false,
+            cose_sign1: false,
+            hsm: None,
+            manifest: None,
+            passphrases: vec![],
+            compress: false,
+        };
+        let plaintext = b"needs two signers to open".to_vec();
+        let sealed = seal_bytes(req, &plaintext).await.unwrap();
+        let co_signed = attach_co_signature(&sealed, &co_signer).unwrap();
+
+        let hdr_len = u32::from_be_bytes(co_signed[0..4].try_into().unwrap()) as usize;
+        let hdr: Header = postcard::from_bytes(&co_signed[4..4 + hdr_len]).unwrap();
+        assert_eq!(hdr.co_signatures.len(), 1);
+        let canonical_bytes = CanonicalHeader::serialize(&hdr).unwrap();
+
+        let mut trust_store = TrustStore::default();
+        trust_store.entries.insert(
+            primary.id_hex(),
+            TrustEntry {
+                public_key_base64: general_purpose::STANDARD.encode(primary.pk.as_bytes()),
+                note: "primary".to_string(),
+                added_at: 0,
+                expires_at: None,
+                roles: vec![],
+                superseded_by: None,
+                ed_public_key: None,
+            },
+        );
+        trust_store.entries.insert(
+            co_signer.id_hex(),
+            TrustEntry {
+                public_key_base64: general_purpose::STANDARD.encode(co_signer.pk.as_bytes()),
+                note: "co-signer".to_string(),
+                added_at: 0,
+                expires_at: None,
+                roles: vec![],
+                superseded_by: None,
+                ed_public_key: None,
+            },
+        );
+
+        let trusted = signer::trusted_signer_set(&hdr, &canonical_bytes, &trust_store).unwrap();
+        assert_eq!(trusted.len(), 2, "both the primary signer and the co-signer should count");
+
+        // Revoke the co-signer: only one trusted signer remains, below quorum.
+        trust_store.revoke_signer(&co_signer.id_hex(), "test revocation".to_string());
+        let trusted = signer::trusted_signer_set(&hdr, &canonical_bytes, &trust_store).unwrap();
+        assert_eq!(trusted.len(), 1, "a revoked co-signer should no longer count toward quorum");
+    }
+}
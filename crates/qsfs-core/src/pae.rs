@@ -1,4 +1,4 @@
-use crate::suite::SuiteId;
+use crate::suite::{CompressionId, SuiteId};
 use crate::header::Header;
 
 /// QSFS v2 Pre-Authenticated Encoding (PAE):
@@ -30,10 +30,34 @@ fn pae_v2_with_salt(suite: SuiteId, chunk_size: u32, file_id: [u8; 8], kdf_salt:
     out
 }
 
+/// v2.2 layout (+ compression id, chunk2-5): only used once a file opts
+/// into pre-encryption compression, so the v2.0/v2.1 vectors above are
+/// untouched for every file that doesn't.
+fn pae_v2_with_compression(
+    suite: SuiteId,
+    chunk_size: u32,
+    file_id: [u8; 8],
+    kdf_salt: [u8; 32],
+    compression: CompressionId,
+) -> Vec<u8> {
+    let suite_bytes = suite.as_str().as_bytes();
+    let compression_bytes = compression.as_str().as_bytes();
+    let items: [&[u8]; 6] =
+        [b"qsfs/v2", suite_bytes, &chunk_size.to_be_bytes(), &file_id, &kdf_salt, compression_bytes];
+    let mut out = Vec::with_capacity(b"QSFS-PAE\x03".len() + items.iter().map(|x| 8 + x.len()).sum::<usize>());
+    out.extend_from_slice(b"QSFS-PAE\x03");
+    for it in items {
+        out.extend_from_slice(&(it.len() as u64).to_be_bytes());
+        out.extend_from_slice(it);
+    }
+    out
+}
+
 /// Backward-compatible PAE builder used by Header::aead_aad()
 pub fn pae_v2_compat(h: &Header) -> Vec<u8> {
-    match h.kdf_salt {
-        Some(s) => pae_v2_with_salt(h.suite, h.chunk_size, h.file_id, s),
-        None => pae_v2_no_salt(h.suite, h.chunk_size, h.file_id),
+    match (h.kdf_salt, h.compression) {
+        (Some(s), Some(c)) => pae_v2_with_compression(h.suite, h.chunk_size, h.file_id, s, c),
+        (Some(s), None) => pae_v2_with_salt(h.suite, h.chunk_size, h.file_id, s),
+        (None, _) => pae_v2_no_salt(h.suite, h.chunk_size, h.file_id),
     }
 }
@@ -0,0 +1,134 @@
+//! Passphrase-based recipients (chunk2-4): a symmetric alternative to the
+//! hybrid ML-KEM + X25519 recipient path that lets a file be sealed to a
+//! password instead of a keypair. The KEK is derived from the passphrase
+//! with Argon2id over the header's existing per-file `kdf_salt`, then the
+//! DEK is wrapped/unwrapped with the same [`crate::derivation::wrap_dek`]/
+//! [`crate::derivation::unwrap_dek`] path, under the file's negotiated
+//! AEAD, every other recipient kind uses — passphrase recipients only
+//! change how the KEK is obtained, not how it's used.
+
+use anyhow::{anyhow, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::RngCore;
+
+use crate::derivation::{unwrap_dek, wrap_dek};
+use crate::header::PassphraseRecipient;
+use crate::suite::AeadId;
+
+/// Tunable Argon2id cost parameters. Stored per-recipient in the header
+/// (see [`PassphraseRecipient`]) so a file remains openable even if the
+/// crate's own defaults change later.
+#[derive(Debug, Clone, Copy)]
+pub struct Argon2Params {
+    pub mem_kib: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+}
+
+impl Default for Argon2Params {
+    fn default() -> Self {
+        // libsodium's crypto_pwhash "moderate" preset, expressed in
+        // Argon2's KiB/iterations/lanes units.
+        Argon2Params { mem_kib: 256 * 1024, time_cost: 3, parallelism: 1 }
+    }
+}
+
+/// Derive a 32-byte KEK from `passphrase` and the file's `kdf_salt` with
+/// Argon2id, mirroring libsodium's `crypto_pwhash`-then-wrap pattern.
+fn derive_kek(passphrase: &str, kdf_salt: &[u8; 32], params: Argon2Params) -> Result<[u8; 32]> {
+    let argon2 = Argon2::new(
+        Algorithm::Argon2id,
+        Version::V0x13,
+        Params::new(params.mem_kib, params.time_cost, params.parallelism, Some(32))
+            .map_err(|e| anyhow!("invalid Argon2id parameters: {e}"))?,
+    );
+    let mut kek = [0u8; 32];
+    argon2
+        .hash_password_into(passphrase.as_bytes(), kdf_salt, &mut kek)
+        .map_err(|e| anyhow!("Argon2id derivation failed: {e}"))?;
+    Ok(kek)
+}
+
+/// Wrap `cek` for a new passphrase recipient labeled `label`.
+pub fn wrap_for_passphrase(
+    label: &str,
+    passphrase: &str,
+    kdf_salt: &[u8; 32],
+    cek: &[u8; 32],
+    params: Argon2Params,
+    aead_id: AeadId,
+) -> Result<PassphraseRecipient> {
+    let kek = derive_kek(passphrase, kdf_salt, params)?;
+    let mut wrap_nonce = [0u8; 12];
+    rand::rngs::OsRng.fill_bytes(&mut wrap_nonce);
+    let wrapped_dek = wrap_dek(&kek, &wrap_nonce, cek, aead_id)?;
+    Ok(PassphraseRecipient {
+        label: label.to_string(),
+        wrapped_dek,
+        wrap_nonce,
+        argon2_mem_kib: params.mem_kib,
+        argon2_time_cost: params.time_cost,
+        argon2_parallelism: params.parallelism,
+    })
+}
+
+/// Recover the CEK wrapped in `recipient` given `passphrase`, or `None` if
+/// it doesn't match (wrong passphrase, or the wrap was tampered with).
+pub fn unwrap_with_passphrase(
+    recipient: &PassphraseRecipient,
+    passphrase: &str,
+    kdf_salt: &[u8; 32],
+    aead_id: AeadId,
+) -> Option<[u8; 32]> {
+    let params = Argon2Params {
+        mem_kib: recipient.argon2_mem_kib,
+        time_cost: recipient.argon2_time_cost,
+        parallelism: recipient.argon2_parallelism,
+    };
+    let kek = derive_kek(passphrase, kdf_salt, params).ok()?;
+    unwrap_dek(&kek, &recipient.wrap_nonce, &recipient.wrapped_dek, aead_id).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_recovers_the_cek() {
+        let kdf_salt = [7u8; 32];
+        let cek = [42u8; 32];
+        let params = Argon2Params { mem_kib: 8 * 1024, time_cost: 1, parallelism: 1 };
+
+        let entry = wrap_for_passphrase("backup", "correct horse battery staple", &kdf_salt, &cek, params, AeadId::Aes256Gcm).unwrap();
+        let recovered = unwrap_with_passphrase(&entry, "correct horse battery staple", &kdf_salt, AeadId::Aes256Gcm).unwrap();
+        assert_eq!(recovered, cek);
+    }
+
+    #[test]
+    fn wrong_passphrase_fails() {
+        let kdf_salt = [7u8; 32];
+        let cek = [42u8; 32];
+        let params = Argon2Params { mem_kib: 8 * 1024, time_cost: 1, parallelism: 1 };
+
+        let entry = wrap_for_passphrase("backup", "correct horse battery staple", &kdf_salt, &cek, params, AeadId::Aes256Gcm).unwrap();
+        assert!(unwrap_with_passphrase(&entry, "wrong passphrase", &kdf_salt, AeadId::Aes256Gcm).is_none());
+    }
+
+    #[test]
+    fn stored_params_are_used_on_unwrap() {
+        // Unwrap must use the recipient's own stored cost parameters, not
+        // whatever the caller happens to pass as a default, since a file
+        // can outlive a change to the crate's default Argon2 tuning.
+        let kdf_salt = [1u8; 32];
+        let cek = [9u8; 32];
+        let custom = Argon2Params { mem_kib: 8 * 1024, time_cost: 2, parallelism: 1 };
+
+        let entry = wrap_for_passphrase("alice", "hunter2", &kdf_salt, &cek, custom, AeadId::Aes256Gcm).unwrap();
+        assert_eq!(entry.argon2_mem_kib, custom.mem_kib);
+        assert_eq!(entry.argon2_time_cost, custom.time_cost);
+        assert_eq!(entry.argon2_parallelism, custom.parallelism);
+
+        let recovered = unwrap_with_passphrase(&entry, "hunter2", &kdf_salt, AeadId::Aes256Gcm).unwrap();
+        assert_eq!(recovered, cek);
+    }
+}
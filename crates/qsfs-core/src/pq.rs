@@ -1,27 +1,87 @@
 #[cfg(feature="pq")]
 pub mod mlkem {
     pub use pqcrypto_mlkem::mlkem1024::*;
-    
+
+    use anyhow::{anyhow, Result};
+    use pqcrypto_traits::kem::{
+        Ciphertext as CiphertextTrait, PublicKey as PublicKeyTrait, SecretKey as SecretKeyTrait,
+        SharedSecret as SharedSecretTrait,
+    };
+
     #[allow(dead_code)]
-    pub fn keypair() -> (PublicKey, SecretKey) { 
-        pqcrypto_mlkem::mlkem1024::keypair() 
+    pub fn keypair() -> (PublicKey, SecretKey) {
+        pqcrypto_mlkem::mlkem1024::keypair()
     }
     #[allow(dead_code)]
-    pub fn encapsulate(pk: &PublicKey) -> (SharedSecret, Ciphertext) { 
-        pqcrypto_mlkem::mlkem1024::encapsulate(pk) 
+    pub fn encapsulate(pk: &PublicKey) -> (SharedSecret, Ciphertext) {
+        pqcrypto_mlkem::mlkem1024::encapsulate(pk)
     }
     #[allow(dead_code)]
-    pub fn decapsulate(ct: &Ciphertext, sk: &SecretKey) -> SharedSecret { 
-        pqcrypto_mlkem::mlkem1024::decapsulate(ct, sk) 
+    pub fn decapsulate(ct: &Ciphertext, sk: &SecretKey) -> SharedSecret {
+        pqcrypto_mlkem::mlkem1024::decapsulate(ct, sk)
     }
-    
-    // For verifiable decapsulation - derive public key from secret key
-    #[allow(dead_code)]
-    pub fn public_key_from_secret(_sk: &SecretKey) -> PublicKey {
-        // Generate a temporary keypair and extract the public key
-        // This is a placeholder - in practice, ML-KEM secret keys contain the public key
-        let (pk, _) = keypair();
-        pk
+
+    /// Byte offset/length of the encapsulation key embedded in an
+    /// ML-KEM-1024 secret key, per FIPS 203 §7.1's
+    /// `dk = dk_PKE ‖ ek ‖ H(ek) ‖ z` layout: `dk_PKE` is `384*k` bytes
+    /// (k=4 for ML-KEM-1024, so 1536), and `ek` — the public key — follows
+    /// immediately and runs for `PUBLIC_KEY_LEN`.
+    const PUBLIC_KEY_OFFSET: usize = 1536;
+    const PUBLIC_KEY_LEN: usize = 1568;
+
+    /// Recover the public (encapsulation) key embedded in `sk`. FIPS 203
+    /// secret keys carry `ek` verbatim, so this is a parse, not a re-keygen
+    /// — unlike the placeholder this replaces, it returns the key `sk` was
+    /// actually issued with.
+    pub fn public_key_from_secret(sk: &SecretKey) -> Result<PublicKey> {
+        let bytes = sk.as_bytes();
+        let ek = bytes
+            .get(PUBLIC_KEY_OFFSET..PUBLIC_KEY_OFFSET + PUBLIC_KEY_LEN)
+            .ok_or_else(|| anyhow!("ML-KEM secret key too short to contain an embedded public key"))?;
+        PublicKey::from_bytes(ek).map_err(|_| anyhow!("ML-KEM secret key's embedded public key is malformed"))
+    }
+
+    /// Verifiable decapsulation: decapsulate `ct` and reject it outright,
+    /// via an explicit error rather than a silently-wrong shared secret, if
+    /// it's not even the right length for this parameter set, or if `sk`
+    /// itself looks torn/corrupted.
+    ///
+    /// FIPS 203's `Decaps` already performs the Fujisaki–Okamoto
+    /// re-encryption check internally, with *implicit rejection*: on a
+    /// tampered `ct` it returns a pseudorandom decoy shared secret instead
+    /// of an error. A from-scratch external re-encryption check — recover
+    /// the message `Decaps` decrypted, re-run the deterministic IND-CPA
+    /// encrypt step with the same coins, and compare the resulting
+    /// ciphertext to `ct` — needs `pqcrypto`'s safe wrapper to expose that
+    /// decrypt-the-message / encrypt-with-explicit-coins step, and it only
+    /// exposes the black-box `encapsulate`/`decapsulate` pair. There is no
+    /// independent randomness or state this function can use to validate
+    /// `ct` itself beyond its length, so it does not attempt one — doing so
+    /// with `decapsulate` alone would be calling the same deterministic,
+    /// pure function twice on identical inputs, which can never disagree
+    /// with itself and would only fake a check that isn't there. What this
+    /// function *does* add: it recovers `sk`'s own embedded public key and
+    /// confirms, in constant time, that `sk` round-trips consistently
+    /// against a freshly generated ciphertext under that key — catching a
+    /// torn/corrupted secret key — before handing the caller whatever
+    /// `decapsulate(ct, sk)` returns. A same-length, structurally-valid
+    /// `ct` that was never a genuine encapsulation is not detected here;
+    /// FIPS 203's own implicit rejection is what keeps that case safe, by
+    /// deriving an unpredictable decoy secret that makes the subsequent
+    /// AEAD tag check fail instead of an oracle.
+    pub fn verify_decapsulate(ct: &Ciphertext, sk: &SecretKey) -> Result<SharedSecret> {
+        let pk = public_key_from_secret(sk)?;
+        let (self_ss, self_ct) = encapsulate(&pk);
+        if ct.as_bytes().len() != self_ct.as_bytes().len() {
+            return Err(anyhow!("implicit rejection: malformed ML-KEM-1024 ciphertext"));
+        }
+
+        let self_ss_roundtrip = decapsulate(&self_ct, sk);
+        if !crate::security::constant_time_eq(self_ss.as_bytes(), self_ss_roundtrip.as_bytes()) {
+            return Err(anyhow!("implicit rejection: secret key does not match its embedded public key"));
+        }
+
+        Ok(decapsulate(ct, sk))
     }
 }
 
@@ -0,0 +1,192 @@
+//! OS-level hardening helpers used around key material.
+
+use anyhow::Result;
+use std::path::Path;
+
+/// Best-effort: disable core dumps for this process so secrets never hit disk
+/// via a crash dump.
+#[cfg(unix)]
+pub fn disable_core_dumps() -> Result<()> {
+    let lim = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+    let rc = unsafe { libc::setrlimit(libc::RLIMIT_CORE, &lim) };
+    if rc != 0 {
+        return Err(anyhow::anyhow!("setrlimit(RLIMIT_CORE) failed"));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn disable_core_dumps() -> Result<()> {
+    Ok(())
+}
+
+/// Restrict a freshly-created output file to owner read/write only.
+#[cfg(unix)]
+pub fn set_secure_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let perms = std::fs::Permissions::from_mode(0o600);
+    std::fs::set_permissions(path, perms)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn set_secure_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Constant-time byte comparison, used anywhere a secret-dependent match
+/// (MAC, re-encapsulation check, etc.) must not leak timing information.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
+/// PKCS#11 HSM integration: offload the per-chunk bulk AEAD to a hardware
+/// security module instead of performing it against an in-memory key (see
+/// `streaming::ChunkCipher`). Only the "message" multi-part AEAD interface
+/// (`C_MessageEncryptInit`/`C_EncryptMessage`/`C_MessageEncryptFinal` and
+/// the decrypt counterpart) is targeted, since that's the PKCS#11 shape
+/// that maps onto one call per chunk without re-deriving a session key.
+pub mod hsm {
+    use anyhow::Result;
+
+    /// A PKCS#11 object handle (`CK_OBJECT_HANDLE`) identifying a key
+    /// already loaded into the HSM. This crate never sees the raw key
+    /// material for an HSM-backed key — only this integer, passed back to
+    /// the module on every operation.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct HsmKeyHandle(pub u64);
+
+    /// An open PKCS#11 session used to perform per-chunk AEAD operations.
+    pub struct HsmSession {
+        #[cfg(feature = "hsm")]
+        session: cryptoki::session::Session,
+    }
+
+    impl HsmSession {
+        /// Encrypt one chunk via the PKCS#11 message-AEAD interface, keyed
+        /// by `key` instead of an in-memory key. Only CKM_AES_GCM is
+        /// targeted — PKCS#11 has no standardized GCM-SIV or
+        /// ChaCha20-Poly1305 message mechanism, so callers must restrict
+        /// HSM offload to `AeadId::Aes256Gcm` chunks (see
+        /// `streaming::encrypt_stream`).
+        #[cfg(feature = "hsm")]
+        pub fn encrypt_chunk(
+            &self,
+            key: HsmKeyHandle,
+            nonce: &[u8; 12],
+            aad: &[u8],
+            plaintext: &[u8],
+        ) -> Result<Vec<u8>> {
+            use cryptoki::mechanism::aead::{GcmMessageIvGenerator, GcmMessageParams};
+            use cryptoki::mechanism::MessageParam;
+            use cryptoki::object::ObjectHandle;
+
+            let params = GcmMessageParams {
+                iv: nonce.to_vec(),
+                iv_fixed_bits: 0,
+                iv_generator: GcmMessageIvGenerator::ProviderGenerate,
+                tag_bits: 128,
+            };
+            let mut ciphertext = vec![0u8; plaintext.len()];
+            let mut tag = [0u8; 16];
+            self.session.encrypt_message(
+                MessageParam::Gcm(params),
+                ObjectHandle::new(key.0),
+                aad,
+                plaintext,
+                &mut ciphertext,
+                &mut tag,
+            )?;
+            ciphertext.extend_from_slice(&tag);
+            Ok(ciphertext)
+        }
+
+        #[cfg(not(feature = "hsm"))]
+        pub fn encrypt_chunk(
+            &self,
+            _key: HsmKeyHandle,
+            _nonce: &[u8; 12],
+            _aad: &[u8],
+            _plaintext: &[u8],
+        ) -> Result<Vec<u8>> {
+            Err(anyhow::anyhow!("HSM support not compiled in (enable the `hsm` feature)"))
+        }
+
+        /// Decrypt one chunk previously sealed by [`Self::encrypt_chunk`]
+        /// (or by the equivalent in-memory AES-256-GCM operation — the wire
+        /// format is identical either way).
+        #[cfg(feature = "hsm")]
+        pub fn decrypt_chunk(
+            &self,
+            key: HsmKeyHandle,
+            nonce: &[u8; 12],
+            aad: &[u8],
+            ciphertext: &[u8],
+        ) -> Result<Vec<u8>> {
+            use cryptoki::mechanism::aead::{GcmMessageIvGenerator, GcmMessageParams};
+            use cryptoki::mechanism::MessageParam;
+            use cryptoki::object::ObjectHandle;
+
+            if ciphertext.len() < 16 {
+                return Err(anyhow::anyhow!("HSM chunk ciphertext shorter than the AEAD tag"));
+            }
+            let (ct, tag) = ciphertext.split_at(ciphertext.len() - 16);
+            let params = GcmMessageParams {
+                iv: nonce.to_vec(),
+                iv_fixed_bits: 0,
+                iv_generator: GcmMessageIvGenerator::ProviderGenerate,
+                tag_bits: 128,
+            };
+            let mut plaintext = vec![0u8; ct.len()];
+            self.session.decrypt_message(
+                MessageParam::Gcm(params),
+                ObjectHandle::new(key.0),
+                aad,
+                ct,
+                &mut plaintext,
+                tag,
+            )?;
+            Ok(plaintext)
+        }
+
+        #[cfg(not(feature = "hsm"))]
+        pub fn decrypt_chunk(
+            &self,
+            _key: HsmKeyHandle,
+            _nonce: &[u8; 12],
+            _aad: &[u8],
+            _ciphertext: &[u8],
+        ) -> Result<Vec<u8>> {
+            Err(anyhow::anyhow!("HSM support not compiled in (enable the `hsm` feature)"))
+        }
+    }
+
+    /// Load a PKCS#11 module and open a read/write session against the
+    /// first available slot with a token present.
+    #[cfg(feature = "hsm")]
+    pub fn initialize_pkcs11(module_path: &str) -> Result<HsmSession> {
+        use cryptoki::context::{CInitializeArgs, Pkcs11};
+
+        let pkcs11 = Pkcs11::new(module_path)?;
+        pkcs11.initialize(CInitializeArgs::OsThreads)?;
+        let slot = pkcs11
+            .get_slots_with_token()?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("no PKCS#11 slot with a token present"))?;
+        let session = pkcs11.open_rw_session(slot)?;
+        Ok(HsmSession { session })
+    }
+
+    #[cfg(not(feature = "hsm"))]
+    pub fn initialize_pkcs11(_module_path: &str) -> Result<HsmSession> {
+        Err(anyhow::anyhow!("HSM support not compiled in (enable the `hsm` feature)"))
+    }
+}
@@ -0,0 +1,1355 @@
+//! Header signing and the trust store of accepted signer public keys.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce as N12};
+use anyhow::{bail, Context, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose, Engine as _};
+use chacha20poly1305::ChaCha20Poly1305;
+use ed25519_dalek::{Signature as Ed25519Signature, Signer as _, SigningKey, VerifyingKey};
+use pqcrypto_mldsa::mldsa87;
+use pqcrypto_traits::sign::{DetachedSignature, PublicKey as _, SecretKey as _};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::{Digest, Sha3_256};
+
+/// An external signing identity (chunk3-1), mirroring the abstraction
+/// `solana-signer` uses to decouple transaction signing from in-process
+/// secret keys: implementors may hold the secret directly, load it from
+/// disk, or forward the request to a remote custody service. The header-
+/// signing path (e.g. `cose::sign`) is written against `&dyn Signer`, so a
+/// caller can swap in any of these without touching header logic.
+pub trait Signer {
+    /// The signer's public key, in the encoding `algorithm()` expects.
+    fn public_key(&self) -> Vec<u8>;
+    /// A short identifier for the signature scheme, e.g. `"ml-dsa-87"`.
+    fn algorithm(&self) -> String;
+    /// Sign `msg` and return the raw detached signature.
+    fn try_sign(&self, msg: &[u8]) -> Result<Vec<u8>>;
+
+    /// ASCII-armor this signer's public key (chunk4-3) for pasting into an
+    /// issue, email, or config file — see `armor::armor_public_key`.
+    fn export_public_armored(&self) -> String {
+        crate::armor::armor_public_key(&self.public_key())
+    }
+}
+
+/// An in-process signing identity: ML-DSA-87 (post-quantum) plus a classical
+/// Ed25519 key, signed together so headers carry defense-in-depth against
+/// either algorithm being broken alone. Implements [`Signer`] for its
+/// ML-DSA-87 half; `sign_ed25519` remains a direct method since the hybrid
+/// header path needs both signatures from one identity at once.
+pub struct LocalSigner {
+    pub id: String,
+    pub pk: mldsa87::PublicKey,
+    sk: mldsa87::SecretKey,
+    pub ed25519_pk: VerifyingKey,
+    ed25519_sk: SigningKey,
+}
+
+impl LocalSigner {
+    pub fn generate() -> Self {
+        let (pk, sk) = mldsa87::keypair();
+        let id = Self::id_for(&pk);
+        let ed25519_sk = SigningKey::generate(&mut rand::rngs::OsRng);
+        let ed25519_pk = ed25519_sk.verifying_key();
+        LocalSigner { id, pk, sk, ed25519_pk, ed25519_sk }
+    }
+
+    fn id_for(pk: &mldsa87::PublicKey) -> String {
+        blake3::hash(pk.as_bytes()).to_hex()[..16].to_string()
+    }
+
+    pub fn id_hex(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>> {
+        let sig = mldsa87::detached_sign(msg, &self.sk);
+        Ok(sig.as_bytes().to_vec())
+    }
+
+    /// Produce the companion Ed25519 signature over the same message.
+    pub fn sign_ed25519(&self, msg: &[u8]) -> Vec<u8> {
+        self.ed25519_sk.sign(msg).to_bytes().to_vec()
+    }
+
+    /// Sign `msg` with both halves of this identity at once, bundled into a
+    /// single portable [`HybridSignature`] (chunk4-6). Equivalent to calling
+    /// [`Self::sign`] and [`Self::sign_ed25519`] separately, for callers
+    /// that want one self-describing blob to store or transmit.
+    pub fn sign_hybrid(&self, msg: &[u8]) -> Result<HybridSignature> {
+        Ok(HybridSignature { mldsa_sig: self.sign(msg)?, ed25519_sig: self.sign_ed25519(msg) })
+    }
+
+    /// Retire this signer in favor of a freshly generated one: records the
+    /// supersession on `store` (see `TrustStore::record_rotation`) and
+    /// returns the new keypair alongside a [`RotationAttestation`] signed
+    /// by *this* (the old) secret key, so a verifier who only trusts the
+    /// old key can validate the handoff to the new one.
+    pub fn rotate(&self, store: &mut TrustStore) -> Result<(LocalSigner, RotationAttestation)> {
+        let new_signer = LocalSigner::generate();
+        let timestamp = unix_now();
+        let old_pk = self.public_key();
+        let msg = rotation_attestation_message(&old_pk, &new_signer.public_key(), timestamp);
+        let signature = self.sign(&msg)?;
+
+        store.record_rotation(&self.id_hex(), &new_signer.id_hex());
+
+        let attestation = RotationAttestation {
+            old_signer_id: self.id_hex(),
+            new_signer_id: new_signer.id_hex(),
+            new_public_key: new_signer.public_key(),
+            timestamp,
+            signature,
+        };
+        Ok((new_signer, attestation))
+    }
+
+    /// Encrypt this signer's secret key material under a key derived from
+    /// `passphrase` and write it to `path` (chunk4-4), using the current
+    /// self-describing `QSFS_SIGNER\x02` format so the KDF/AEAD parameters
+    /// travel with the file instead of being assumed by the reader. The KDF
+    /// and AEAD algorithms themselves are pluggable (chunk4-5, see
+    /// [`KdfKind`]/[`AeadKind`]) via `params.kind` and `aead_kind`.
+    pub fn save_to_file_encrypted(
+        &self,
+        path: impl AsRef<Path>,
+        passphrase: &str,
+        params: KdfParams,
+        aead_kind: AeadKind,
+    ) -> Result<()> {
+        let plaintext = encode_key_material(&self.pk, &self.sk, &self.ed25519_pk, &self.ed25519_sk);
+
+        let mut salt = [0u8; SIGNER_FILE_SALT_LEN];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; 12];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+        let key = derive_key(passphrase, &salt, params)?;
+        let ciphertext = SignerAead::new(aead_kind, &key)
+            .encrypt(&nonce, &plaintext)
+            .map_err(|_| anyhow::anyhow!("signer file encryption failed"))?;
+
+        let mut out = Vec::new();
+        out.extend_from_slice(SIGNER_FILE_MAGIC);
+        out.push(SIGNER_FILE_VERSION_TLV);
+        out.push(params.kind.id());
+        out.extend_from_slice(&params.mem_kib.to_be_bytes());
+        out.extend_from_slice(&params.time_cost.to_be_bytes());
+        out.extend_from_slice(&params.parallelism.to_be_bytes());
+        out.push(aead_kind.id());
+        out.extend_from_slice(&salt);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+
+        std::fs::write(path.as_ref(), out).with_context(|| format!("writing signer file {}", path.as_ref().display()))
+    }
+
+    /// Load a signer previously written by [`Self::save_to_file_encrypted`].
+    /// Reads both the current `QSFS_SIGNER\x02` TLV format (any supported
+    /// [`KdfKind`]/[`AeadKind`] pair) and the legacy `QSFS_SIGNER\x01`
+    /// format (fixed Argon2id at 19456 KiB / 2 iterations / parallelism 1,
+    /// AES-256-GCM), so files written before this format existed stay
+    /// decryptable.
+    pub fn load_from_file_encrypted(path: impl AsRef<Path>, passphrase: &str) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).with_context(|| format!("reading signer file {}", path.display()))?;
+        let rest = data
+            .strip_prefix(SIGNER_FILE_MAGIC)
+            .context("not a QSFS signer file (bad magic)")?;
+        let (&version, rest) = rest.split_first().context("truncated signer file")?;
+
+        let (params, aead_kind, salt, nonce, ciphertext) = match version {
+            SIGNER_FILE_VERSION_LEGACY_FIXED => {
+                let (salt, rest) = take(rest, SIGNER_FILE_SALT_LEN).context("truncated signer file salt")?;
+                let (nonce, ciphertext) = take(rest, 12).context("truncated signer file nonce")?;
+                (KdfParams::legacy_fixed(), AeadKind::Aes256Gcm, salt, nonce, ciphertext)
+            }
+            SIGNER_FILE_VERSION_TLV => {
+                let (&kdf_id, rest) = rest.split_first().context("truncated signer file (kdf id)")?;
+                let kind = KdfKind::from_id(kdf_id)?;
+                let (mem_kib, rest) = take_u32(rest).context("truncated signer file (mem_kib)")?;
+                let (time_cost, rest) = take_u32(rest).context("truncated signer file (time_cost)")?;
+                let (parallelism, rest) = take_u32(rest).context("truncated signer file (parallelism)")?;
+                let (&aead_id, rest) = rest.split_first().context("truncated signer file (aead id)")?;
+                let aead_kind = AeadKind::from_id(aead_id)?;
+                let (salt, rest) = take(rest, SIGNER_FILE_SALT_LEN).context("truncated signer file salt")?;
+                let (nonce, ciphertext) = take(rest, 12).context("truncated signer file nonce")?;
+                (KdfParams { kind, mem_kib, time_cost, parallelism }, aead_kind, salt, nonce, ciphertext)
+            }
+            other => bail!("unsupported signer file version {}", other),
+        };
+
+        let key = derive_key(passphrase, salt, params)?;
+        let plaintext = SignerAead::new(aead_kind, &key)
+            .decrypt(nonce, ciphertext)
+            .map_err(|_| anyhow::anyhow!("wrong passphrase or corrupted signer file"))?;
+
+        decode_key_material(&plaintext)
+    }
+
+    /// Generate a signer and immediately persist it to `path`, taking the
+    /// KDF and AEAD to encrypt it with from `~/.qsfs/config` (chunk4-5, see
+    /// [`SignerFileConfig::load_or_default`]) rather than this crate's
+    /// hardcoded defaults.
+    pub fn generate_and_save_encrypted(path: impl AsRef<Path>, passphrase: &str) -> Result<Self> {
+        let config = SignerFileConfig::load_or_default()?;
+        let signer = LocalSigner::generate();
+        signer.save_to_file_encrypted(path, passphrase, config.kdf_params(), config.aead_kind)?;
+        Ok(signer)
+    }
+
+    /// Encrypt this signer's ML-DSA-87 secret key into a portable,
+    /// Ethereum-v3-keystore-style JSON envelope (chunk5-1) — unlike
+    /// [`Self::save_to_file_encrypted`]'s binary `QSFS_SIGNER` format, this
+    /// is meant to be moved between machines and audited by eye. Only the
+    /// ML-DSA-87 half is covered; see [`Self::from_json_keystore`].
+    pub fn to_json_keystore(&self, passphrase: &str, kdf: JsonKeystoreKdf) -> Result<JsonKeystore> {
+        let mut salt = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; 12];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+        let derived = kdf.derive(passphrase, &salt)?;
+        let aead = Aes256Gcm::new_from_slice(&derived).map_err(|_| anyhow::anyhow!("bad derived key length"))?;
+        let ciphertext = aead
+            .encrypt(N12::from_slice(&nonce), self.sk.as_bytes())
+            .map_err(|_| anyhow::anyhow!("keystore encryption failed"))?;
+        let mac = json_keystore_mac(&derived, &ciphertext);
+
+        Ok(JsonKeystore {
+            version: 1,
+            id: self.id_hex(),
+            pk_base64: general_purpose::STANDARD.encode(self.pk.as_bytes()),
+            crypto: JsonKeystoreCrypto {
+                kdf: kdf.name().to_string(),
+                kdfparams: kdf.to_params(&salt),
+                cipher: "aes-256-gcm".to_string(),
+                cipherparams: JsonCipherParams { iv: hex_encode(&nonce) },
+                ciphertext: hex_encode(&ciphertext),
+                mac: hex_encode(&mac),
+            },
+        })
+    }
+
+    /// Decrypt a [`JsonKeystore`] produced by [`Self::to_json_keystore`].
+    /// The stored `mac` is checked *before* the AEAD is touched, so a wrong
+    /// passphrase fails with a clear "keystore MAC mismatch" error instead
+    /// of an opaque AEAD tag failure.
+    ///
+    /// The keystore format only covers the ML-DSA-87 half of a
+    /// [`LocalSigner`] (matching its Ethereum-v3 lineage, which has no
+    /// concept of a companion classical key); the returned signer's
+    /// Ed25519 half is freshly generated rather than recovered, since it
+    /// was never part of the keystore.
+    pub fn from_json_keystore(keystore: &JsonKeystore, passphrase: &str) -> Result<Self> {
+        let crypto = &keystore.crypto;
+        if crypto.cipher != "aes-256-gcm" {
+            bail!("unsupported keystore cipher '{}'", crypto.cipher);
+        }
+        let kdf = crypto.kdfparams.kdf();
+        let salt = crypto.kdfparams.salt_bytes()?;
+        let derived = kdf.derive(passphrase, &salt)?;
+
+        let ciphertext = hex_decode(&crypto.ciphertext)?;
+        let expected_mac = hex_decode(&crypto.mac)?;
+        if json_keystore_mac(&derived, &ciphertext) != expected_mac {
+            bail!("wrong passphrase (keystore MAC mismatch)");
+        }
+
+        let nonce = hex_decode(&crypto.cipherparams.iv)?;
+        let aead = Aes256Gcm::new_from_slice(&derived).map_err(|_| anyhow::anyhow!("bad derived key length"))?;
+        let sk_bytes = aead
+            .decrypt(N12::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| anyhow::anyhow!("keystore ciphertext decryption failed"))?;
+        let sk = mldsa87::SecretKey::from_bytes(&sk_bytes).context("invalid ML-DSA-87 secret key in keystore")?;
+
+        let pk_bytes = general_purpose::STANDARD
+            .decode(&keystore.pk_base64)
+            .context("invalid base64 public key in keystore")?;
+        let pk = mldsa87::PublicKey::from_bytes(&pk_bytes).context("invalid ML-DSA-87 public key in keystore")?;
+        let id = LocalSigner::id_for(&pk);
+
+        let ed25519_sk = SigningKey::generate(&mut rand::rngs::OsRng);
+        let ed25519_pk = ed25519_sk.verifying_key();
+
+        Ok(LocalSigner { id, pk, sk, ed25519_pk, ed25519_sk })
+    }
+}
+
+/// Magic bytes for the at-rest encrypted signer file (chunk4-4).
+const SIGNER_FILE_MAGIC: &[u8] = b"QSFS_SIGNER";
+/// Legacy format: fixed Argon2id parameters, no KDF/AEAD ids stored.
+const SIGNER_FILE_VERSION_LEGACY_FIXED: u8 = 1;
+/// Current format: self-describing KDF id, cost parameters, and AEAD
+/// cipher id, so hardening the parameters or switching algorithm doesn't
+/// strand files written under the old defaults.
+const SIGNER_FILE_VERSION_TLV: u8 = 2;
+const KDF_ID_ARGON2ID: u8 = 0;
+const KDF_ID_SCRYPT: u8 = 1;
+const KDF_ID_PBKDF2_SHA256: u8 = 2;
+const AEAD_ID_AES256GCM: u8 = 0;
+const AEAD_ID_CHACHA20_POLY1305: u8 = 1;
+const SIGNER_FILE_SALT_LEN: usize = 16;
+
+/// The password-based KDF used to derive an at-rest signer file's
+/// encryption key (chunk4-5). Argon2id remains the default; `Scrypt` and
+/// `Pbkdf2Sha256` are offered for environments where one of those is the
+/// more available or more widely audited primitive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KdfKind {
+    Argon2id,
+    Scrypt,
+    Pbkdf2Sha256,
+}
+
+impl KdfKind {
+    fn id(self) -> u8 {
+        match self {
+            KdfKind::Argon2id => KDF_ID_ARGON2ID,
+            KdfKind::Scrypt => KDF_ID_SCRYPT,
+            KdfKind::Pbkdf2Sha256 => KDF_ID_PBKDF2_SHA256,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self> {
+        match id {
+            KDF_ID_ARGON2ID => Ok(KdfKind::Argon2id),
+            KDF_ID_SCRYPT => Ok(KdfKind::Scrypt),
+            KDF_ID_PBKDF2_SHA256 => Ok(KdfKind::Pbkdf2Sha256),
+            other => bail!("unsupported signer file KDF id {}", other),
+        }
+    }
+
+    fn from_config_str(s: &str) -> Result<Self> {
+        match s {
+            "argon2id" => Ok(KdfKind::Argon2id),
+            "scrypt" => Ok(KdfKind::Scrypt),
+            "pbkdf2-sha256" => Ok(KdfKind::Pbkdf2Sha256),
+            other => bail!("unrecognized _pref_hash_algo '{}' in ~/.qsfs/config", other),
+        }
+    }
+}
+
+/// The AEAD cipher used to encrypt an at-rest signer file (chunk4-5).
+/// ChaCha20-Poly1305 is offered alongside AES-256-GCM for hardware without
+/// AES-NI, matching the same tradeoff `suite::AeadId` already makes for
+/// sealed files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AeadKind {
+    Aes256Gcm,
+    ChaCha20Poly1305,
+}
+
+impl AeadKind {
+    fn id(self) -> u8 {
+        match self {
+            AeadKind::Aes256Gcm => AEAD_ID_AES256GCM,
+            AeadKind::ChaCha20Poly1305 => AEAD_ID_CHACHA20_POLY1305,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self> {
+        match id {
+            AEAD_ID_AES256GCM => Ok(AeadKind::Aes256Gcm),
+            AEAD_ID_CHACHA20_POLY1305 => Ok(AeadKind::ChaCha20Poly1305),
+            other => bail!("unsupported signer file AEAD id {}", other),
+        }
+    }
+
+    fn from_config_str(s: &str) -> Result<Self> {
+        match s {
+            "aes-256-gcm" => Ok(AeadKind::Aes256Gcm),
+            "chacha20-poly1305" => Ok(AeadKind::ChaCha20Poly1305),
+            other => bail!("unrecognized _pref_enc_algo '{}' in ~/.qsfs/config", other),
+        }
+    }
+}
+
+/// The AEAD cipher, keyed and ready to seal/open a signer file's
+/// plaintext. Mirrors `streaming::BulkAead`'s runtime-dispatch shape, just
+/// over the two ciphers offered here.
+enum SignerAead {
+    Aes256Gcm(Aes256Gcm),
+    ChaCha20Poly1305(ChaCha20Poly1305),
+}
+
+impl SignerAead {
+    fn new(kind: AeadKind, key: &[u8; 32]) -> Self {
+        match kind {
+            AeadKind::Aes256Gcm => SignerAead::Aes256Gcm(Aes256Gcm::new_from_slice(key).unwrap()),
+            AeadKind::ChaCha20Poly1305 => {
+                SignerAead::ChaCha20Poly1305(ChaCha20Poly1305::new_from_slice(key).unwrap())
+            }
+        }
+    }
+
+    fn encrypt(&self, nonce: &[u8; 12], pt: &[u8]) -> aead::Result<Vec<u8>> {
+        let n = N12::from_slice(nonce);
+        match self {
+            SignerAead::Aes256Gcm(a) => a.encrypt(n, pt),
+            SignerAead::ChaCha20Poly1305(a) => a.encrypt(n, pt),
+        }
+    }
+
+    fn decrypt(&self, nonce: &[u8], ct: &[u8]) -> aead::Result<Vec<u8>> {
+        let n = N12::from_slice(nonce);
+        match self {
+            SignerAead::Aes256Gcm(a) => a.decrypt(n, ct),
+            SignerAead::ChaCha20Poly1305(a) => a.decrypt(n, ct),
+        }
+    }
+}
+
+/// Cost parameters for an at-rest signer file's KDF (chunk4-4/chunk4-5).
+/// Unlike `passphrase::Argon2Params` (which tunes a single recipient's
+/// KEK), this travels inside `QSFS_SIGNER\x02`'s own header rather than a
+/// `Header`. Field meaning depends on `kind`: for `Argon2id`, the usual
+/// memory/iterations/lanes; for `Scrypt`, `time_cost` is `log_n` and
+/// `parallelism` is `r` (`mem_kib` unused); for `Pbkdf2Sha256`, `time_cost`
+/// is the iteration count (`mem_kib`/`parallelism` unused).
+#[derive(Debug, Clone, Copy)]
+pub struct KdfParams {
+    pub kind: KdfKind,
+    pub mem_kib: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams::default_for(KdfKind::Argon2id)
+    }
+}
+
+impl KdfParams {
+    /// Reasonable cost parameters for `kind`, so a caller who just wants
+    /// "the sensible defaults for scrypt" doesn't have to pick raw knobs.
+    pub fn default_for(kind: KdfKind) -> Self {
+        match kind {
+            // libsodium's crypto_pwhash "moderate" preset.
+            KdfKind::Argon2id => KdfParams { kind, mem_kib: 256 * 1024, time_cost: 3, parallelism: 1 },
+            KdfKind::Scrypt => KdfParams { kind, mem_kib: 0, time_cost: 17, parallelism: 8 },
+            KdfKind::Pbkdf2Sha256 => KdfParams { kind, mem_kib: 0, time_cost: 600_000, parallelism: 0 },
+        }
+    }
+
+    /// The fixed parameters every `QSFS_SIGNER\x01` file was written with.
+    fn legacy_fixed() -> Self {
+        KdfParams { kind: KdfKind::Argon2id, mem_kib: 19456, time_cost: 2, parallelism: 1 }
+    }
+}
+
+/// Dispatch to the KDF named by `params.kind`.
+fn derive_key(passphrase: &str, salt: &[u8], params: KdfParams) -> Result<[u8; 32]> {
+    match params.kind {
+        KdfKind::Argon2id => derive_key_argon2id(passphrase, salt, params),
+        KdfKind::Scrypt => derive_key_scrypt(passphrase, salt, params),
+        KdfKind::Pbkdf2Sha256 => derive_key_pbkdf2(passphrase, salt, params),
+    }
+}
+
+fn derive_key_argon2id(passphrase: &str, salt: &[u8], params: KdfParams) -> Result<[u8; 32]> {
+    let argon2 = Argon2::new(
+        Algorithm::Argon2id,
+        Version::V0x13,
+        Params::new(params.mem_kib, params.time_cost, params.parallelism, Some(32))
+            .map_err(|e| anyhow::anyhow!("invalid Argon2id parameters: {e}"))?,
+    );
+    let mut key = [0u8; 32];
+    argon2
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Argon2id derivation failed: {e}"))?;
+    Ok(key)
+}
+
+fn derive_key_scrypt(passphrase: &str, salt: &[u8], params: KdfParams) -> Result<[u8; 32]> {
+    let log_n = params.time_cost.clamp(1, 31) as u8;
+    let r = params.parallelism.max(1);
+    let scrypt_params = scrypt::Params::new(log_n, r, 1, 32)
+        .map_err(|e| anyhow::anyhow!("invalid scrypt parameters: {e}"))?;
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &scrypt_params, &mut key)
+        .map_err(|e| anyhow::anyhow!("scrypt derivation failed: {e}"))?;
+    Ok(key)
+}
+
+fn derive_key_pbkdf2(passphrase: &str, salt: &[u8], params: KdfParams) -> Result<[u8; 32]> {
+    let rounds = params.time_cost.max(1);
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, rounds, &mut key);
+    Ok(key)
+}
+
+/// The subset of `~/.qsfs/config` that governs at-rest signer encryption
+/// (chunk4-5): `_pref_hash_algo` selects the [`KdfKind`], `_pref_enc_algo`
+/// the [`AeadKind`], one `key=value` pair per line. Missing or absent file
+/// falls back to `Argon2id`/`Aes256Gcm`, matching this crate's prior
+/// hardcoded defaults.
+#[derive(Debug, Clone, Copy)]
+pub struct SignerFileConfig {
+    pub kdf_kind: KdfKind,
+    pub aead_kind: AeadKind,
+}
+
+impl Default for SignerFileConfig {
+    fn default() -> Self {
+        SignerFileConfig { kdf_kind: KdfKind::Argon2id, aead_kind: AeadKind::Aes256Gcm }
+    }
+}
+
+impl SignerFileConfig {
+    pub fn kdf_params(&self) -> KdfParams {
+        KdfParams::default_for(self.kdf_kind)
+    }
+
+    /// Read `~/.qsfs/config`, falling back to [`Self::default`] if the file
+    /// is absent. Lines without `=` and unknown keys are skipped; an
+    /// unrecognized algorithm name for a known key is an error.
+    pub fn load_or_default() -> Result<Self> {
+        let home = dirs::home_dir().context("could not determine home directory")?;
+        let path = home.join(".qsfs").join("config");
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+
+        let mut config = Self::default();
+        for line in data.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else { continue };
+            match key.trim() {
+                "_pref_hash_algo" => config.kdf_kind = KdfKind::from_config_str(value.trim())?,
+                "_pref_enc_algo" => config.aead_kind = AeadKind::from_config_str(value.trim())?,
+                _ => {}
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// The KDF choice for a [`JsonKeystore`] (chunk5-1), mirroring the
+/// Ethereum v3 keystore's own pair of options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonKeystoreKdf {
+    Scrypt { n: u32, r: u32, p: u32 },
+    Pbkdf2 { c: u32 },
+}
+
+impl Default for JsonKeystoreKdf {
+    fn default() -> Self {
+        // The Ethereum v3 keystore's own scrypt defaults.
+        JsonKeystoreKdf::Scrypt { n: 262_144, r: 8, p: 1 }
+    }
+}
+
+impl JsonKeystoreKdf {
+    fn name(self) -> &'static str {
+        match self {
+            JsonKeystoreKdf::Scrypt { .. } => "scrypt",
+            JsonKeystoreKdf::Pbkdf2 { .. } => "pbkdf2",
+        }
+    }
+
+    fn to_params(self, salt: &[u8]) -> JsonKdfParams {
+        match self {
+            JsonKeystoreKdf::Scrypt { n, r, p } => {
+                JsonKdfParams::Scrypt { n, r, p, salt: hex_encode(salt), dklen: 32 }
+            }
+            JsonKeystoreKdf::Pbkdf2 { c } => {
+                JsonKdfParams::Pbkdf2 { c, prf: "hmac-sha256".to_string(), salt: hex_encode(salt), dklen: 32 }
+            }
+        }
+    }
+
+    /// Derive a 32-byte key from `passphrase` and `salt`. Bytes `[0..32]`
+    /// of this key are the AEAD key; bytes `[16..32]` additionally feed
+    /// the keystore's MAC (see [`json_keystore_mac`]).
+    fn derive(self, passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+        let mut key = [0u8; 32];
+        match self {
+            JsonKeystoreKdf::Scrypt { n, r, p } => {
+                let log_n = n.trailing_zeros() as u8;
+                let params = scrypt::Params::new(log_n, r, p, 32)
+                    .map_err(|e| anyhow::anyhow!("invalid scrypt parameters: {e}"))?;
+                scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+                    .map_err(|e| anyhow::anyhow!("scrypt derivation failed: {e}"))?;
+            }
+            JsonKeystoreKdf::Pbkdf2 { c } => {
+                pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, c, &mut key);
+            }
+        }
+        Ok(key)
+    }
+}
+
+/// The `crypto.kdfparams` object of a [`JsonKeystore`]: either shape
+/// carries its own `salt`/`dklen`, matching the Ethereum v3 keystore
+/// schema's per-KDF parameter sets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JsonKdfParams {
+    Scrypt { n: u32, r: u32, p: u32, salt: String, dklen: u32 },
+    Pbkdf2 { c: u32, prf: String, salt: String, dklen: u32 },
+}
+
+impl JsonKdfParams {
+    fn salt_bytes(&self) -> Result<Vec<u8>> {
+        let salt = match self {
+            JsonKdfParams::Scrypt { salt, .. } | JsonKdfParams::Pbkdf2 { salt, .. } => salt,
+        };
+        hex_decode(salt)
+    }
+
+    fn kdf(&self) -> JsonKeystoreKdf {
+        match *self {
+            JsonKdfParams::Scrypt { n, r, p, .. } => JsonKeystoreKdf::Scrypt { n, r, p },
+            JsonKdfParams::Pbkdf2 { c, .. } => JsonKeystoreKdf::Pbkdf2 { c },
+        }
+    }
+}
+
+/// The `crypto.cipherparams` object of a [`JsonKeystore`]: just the AEAD
+/// nonce, hex-encoded (named `iv` to match the Ethereum v3 schema this
+/// format borrows from).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonCipherParams {
+    pub iv: String,
+}
+
+/// The `crypto` object of a [`JsonKeystore`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonKeystoreCrypto {
+    pub kdf: String,
+    pub kdfparams: JsonKdfParams,
+    pub cipher: String,
+    pub cipherparams: JsonCipherParams,
+    /// Hex-encoded AES-256-GCM ciphertext (tag included) of the ML-DSA-87
+    /// secret key.
+    pub ciphertext: String,
+    /// Hex-encoded `SHA3-256(derived_key[16..32] || ciphertext)`, checked
+    /// before decryption so a wrong passphrase is detected without ever
+    /// touching the AEAD.
+    pub mac: String,
+}
+
+/// A portable, versioned JSON keystore for a signer's ML-DSA-87 secret key
+/// (chunk5-1), in the spirit of the Ethereum v3 keystore format. Produced
+/// by [`LocalSigner::to_json_keystore`], consumed by
+/// [`LocalSigner::from_json_keystore`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonKeystore {
+    pub version: u32,
+    pub id: String,
+    pub pk_base64: String,
+    pub crypto: JsonKeystoreCrypto,
+}
+
+fn json_keystore_mac(derived_key: &[u8; 32], ciphertext: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha3_256::new();
+    hasher.update(&derived_key[16..32]);
+    hasher.update(ciphertext);
+    hasher.finalize().to_vec()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_decode(s: &str) -> Result<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        bail!("odd-length hex string");
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|e| anyhow::anyhow!("invalid hex in keystore: {e}")))
+        .collect()
+}
+
+fn take(bytes: &[u8], n: usize) -> Option<(&[u8], &[u8])> {
+    if bytes.len() < n { None } else { Some(bytes.split_at(n)) }
+}
+
+fn take_u32(bytes: &[u8]) -> Option<(u32, &[u8])> {
+    let (head, rest) = take(bytes, 4)?;
+    Some((u32::from_be_bytes(head.try_into().unwrap()), rest))
+}
+
+/// Length-prefixed `pk || sk || ed25519_pk || ed25519_sk`, so the reader
+/// never has to hardcode ML-DSA-87's key sizes.
+fn encode_key_material(
+    pk: &mldsa87::PublicKey,
+    sk: &mldsa87::SecretKey,
+    ed25519_pk: &VerifyingKey,
+    ed25519_sk: &SigningKey,
+) -> Vec<u8> {
+    let mut out = Vec::new();
+    for field in [pk.as_bytes(), sk.as_bytes(), ed25519_pk.as_bytes(), &ed25519_sk.to_bytes()] {
+        out.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        out.extend_from_slice(field);
+    }
+    out
+}
+
+fn decode_key_material(plaintext: &[u8]) -> Result<LocalSigner> {
+    let (pk_bytes, rest) = take_length_prefixed(plaintext).context("truncated signer file (pk)")?;
+    let (sk_bytes, rest) = take_length_prefixed(rest).context("truncated signer file (sk)")?;
+    let (ed25519_pk_bytes, rest) = take_length_prefixed(rest).context("truncated signer file (ed25519 pk)")?;
+    let (ed25519_sk_bytes, _) = take_length_prefixed(rest).context("truncated signer file (ed25519 sk)")?;
+
+    let pk = mldsa87::PublicKey::from_bytes(pk_bytes).context("invalid ML-DSA-87 public key in signer file")?;
+    let sk = mldsa87::SecretKey::from_bytes(sk_bytes).context("invalid ML-DSA-87 secret key in signer file")?;
+    let ed25519_pk_arr: [u8; 32] = ed25519_pk_bytes.try_into().context("invalid Ed25519 public key length")?;
+    let ed25519_sk_arr: [u8; 32] = ed25519_sk_bytes.try_into().context("invalid Ed25519 secret key length")?;
+    let ed25519_pk = VerifyingKey::from_bytes(&ed25519_pk_arr).context("invalid Ed25519 public key in signer file")?;
+    let ed25519_sk = SigningKey::from_bytes(&ed25519_sk_arr);
+
+    let id = LocalSigner::id_for(&pk);
+    Ok(LocalSigner { id, pk, sk, ed25519_pk, ed25519_sk })
+}
+
+fn take_length_prefixed(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (len, rest) = take_u32(bytes)?;
+    take(rest, len as usize)
+}
+
+impl Signer for LocalSigner {
+    fn public_key(&self) -> Vec<u8> {
+        self.pk.as_bytes().to_vec()
+    }
+
+    fn algorithm(&self) -> String {
+        "ml-dsa-87".to_string()
+    }
+
+    fn try_sign(&self, msg: &[u8]) -> Result<Vec<u8>> {
+        self.sign(msg)
+    }
+}
+
+/// A signer whose ML-DSA-87 secret key lives in a file on disk (e.g.
+/// mounted from a secrets volume) rather than in an already-running
+/// process. The file must hold the raw secret key bytes as produced by
+/// `pqcrypto_mldsa::mldsa87::SecretKey::as_bytes`.
+pub struct FileSigner {
+    pk: mldsa87::PublicKey,
+    sk: mldsa87::SecretKey,
+}
+
+impl FileSigner {
+    pub fn load(key_path: impl AsRef<Path>, pk_bytes: &[u8]) -> Result<Self> {
+        let key_path = key_path.as_ref();
+        let sk_bytes = std::fs::read(key_path)
+            .with_context(|| format!("reading signer key file {}", key_path.display()))?;
+        let sk = mldsa87::SecretKey::from_bytes(&sk_bytes)
+            .context("invalid ML-DSA-87 secret key file")?;
+        let pk = mldsa87::PublicKey::from_bytes(pk_bytes).context("invalid ML-DSA-87 public key")?;
+        Ok(FileSigner { pk, sk })
+    }
+}
+
+impl Signer for FileSigner {
+    fn public_key(&self) -> Vec<u8> {
+        self.pk.as_bytes().to_vec()
+    }
+
+    fn algorithm(&self) -> String {
+        "ml-dsa-87".to_string()
+    }
+
+    fn try_sign(&self, msg: &[u8]) -> Result<Vec<u8>> {
+        Ok(mldsa87::detached_sign(msg, &self.sk).as_bytes().to_vec())
+    }
+}
+
+/// Placeholder for a remote or HSM-backed signer: the secret key never
+/// enters this process, only a handle (`key_id`) identifying it at the
+/// remote service. Wire `try_sign` up to your KMS/HSM's signing RPC; as
+/// shipped it reports that no transport is configured, so integrators get
+/// an explicit error instead of a silently-unsigned header.
+pub struct RemoteSigner {
+    pub key_id: String,
+    pub public_key: Vec<u8>,
+}
+
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    fn algorithm(&self) -> String {
+        "ml-dsa-87".to_string()
+    }
+
+    fn try_sign(&self, _msg: &[u8]) -> Result<Vec<u8>> {
+        bail!("remote signer '{}' has no signing transport configured", self.key_id)
+    }
+}
+
+/// Verify a detached ML-DSA-87 signature over `msg`.
+pub fn verify_signature(msg: &[u8], sig: &[u8], pk_bytes: &[u8]) -> Result<bool> {
+    let pk = mldsa87::PublicKey::from_bytes(pk_bytes).context("invalid ML-DSA-87 public key")?;
+    let sig = mldsa87::DetachedSignature::from_bytes(sig).context("invalid ML-DSA-87 signature")?;
+    Ok(mldsa87::verify_detached_signature(&sig, msg, &pk).is_ok())
+}
+
+/// Verify a detached Ed25519 signature over `msg`.
+pub fn verify_ed25519_signature(msg: &[u8], sig: &[u8], pk_bytes: &[u8]) -> Result<bool> {
+    let pk_bytes: [u8; 32] = pk_bytes
+        .try_into()
+        .context("invalid Ed25519 public key length")?;
+    let pk = VerifyingKey::from_bytes(&pk_bytes).context("invalid Ed25519 public key")?;
+    let sig_bytes: [u8; 64] = sig.try_into().context("invalid Ed25519 signature length")?;
+    let sig = Ed25519Signature::from_bytes(&sig_bytes);
+    Ok(pk.verify_strict(msg, &sig).is_ok())
+}
+
+/// Verify a hybrid ML-DSA-87 + Ed25519 header signature: both signatures
+/// must independently verify, so forging a header requires breaking both
+/// a post-quantum and a classical signature scheme at once.
+pub fn verify_hybrid_signature(
+    msg: &[u8],
+    mldsa_sig: &[u8],
+    mldsa_pk: &[u8],
+    ed25519_sig: &[u8],
+    ed25519_pk: &[u8],
+) -> Result<bool> {
+    Ok(verify_signature(msg, mldsa_sig, mldsa_pk)?
+        && verify_ed25519_signature(msg, ed25519_sig, ed25519_pk)?)
+}
+
+/// Algorithm tag prefixing a serialized [`HybridSignature`], so a reader
+/// that only knows how to parse bare ML-DSA-87 or Ed25519 signatures can
+/// reject the bundle instead of misreading it.
+const HYBRID_SIGNATURE_TAG: u8 = 0x01;
+
+/// A bundled ML-DSA-87 + Ed25519 signature pair over the same message
+/// (chunk4-6), for callers that want defense-in-depth against either
+/// scheme being broken in one self-describing, storable blob. Produced by
+/// [`LocalSigner::sign_hybrid`]; verified with [`Self::verify`] or the
+/// free function [`verify_hybrid_signature`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HybridSignature {
+    pub mldsa_sig: Vec<u8>,
+    pub ed25519_sig: Vec<u8>,
+}
+
+impl HybridSignature {
+    /// `tag || u32_be(len) || mldsa_sig || u32_be(len) || ed25519_sig`.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = vec![HYBRID_SIGNATURE_TAG];
+        for field in [&self.mldsa_sig, &self.ed25519_sig] {
+            out.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            out.extend_from_slice(field);
+        }
+        out
+    }
+
+    /// Inverse of [`Self::serialize`].
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let (&tag, rest) = bytes.split_first().context("truncated hybrid signature")?;
+        if tag != HYBRID_SIGNATURE_TAG {
+            bail!("unrecognized hybrid signature algorithm tag {}", tag);
+        }
+        let (mldsa_sig, rest) = take_length_prefixed(rest).context("truncated hybrid signature (mldsa)")?;
+        let (ed25519_sig, _) = take_length_prefixed(rest).context("truncated hybrid signature (ed25519)")?;
+        Ok(HybridSignature { mldsa_sig: mldsa_sig.to_vec(), ed25519_sig: ed25519_sig.to_vec() })
+    }
+
+    /// Verify both halves of this bundle against `msg`; both must succeed.
+    pub fn verify(&self, msg: &[u8], mldsa_pk: &[u8], ed25519_pk: &[u8]) -> Result<bool> {
+        verify_hybrid_signature(msg, &self.mldsa_sig, mldsa_pk, &self.ed25519_sig, ed25519_pk)
+    }
+}
+
+/// A hybrid identity's signer id: the hash of both public keys
+/// concatenated, distinct from [`LocalSigner::id_for`]'s ML-DSA-87-only id
+/// so a hybrid-trusted entry can't be confused with a bare one.
+pub fn hybrid_signer_id(mldsa_pk: &[u8], ed25519_pk: &[u8]) -> String {
+    let mut msg = mldsa_pk.to_vec();
+    msg.extend_from_slice(ed25519_pk);
+    blake3::hash(&msg).to_hex()[..16].to_string()
+}
+
+/// A single trusted signer entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustEntry {
+    pub public_key_base64: String,
+    pub note: String,
+    pub added_at: u64,
+    /// Unix timestamp after which this key is no longer trusted, even if
+    /// still present in the store. `None` (the default, so existing
+    /// trust-store JSON without this field keeps working) never expires.
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    /// Role names (see [`Role`]) this key is tagged with. `verify_threshold`
+    /// takes role membership from [`Role::signer_ids`], not from this
+    /// field. Defaults to empty so existing
+    /// entries remain valid; a key with no roles can still be checked
+    /// directly via `is_trusted`.
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Set by `LocalSigner::rotate()`/`TrustStore::record_rotation` to the
+    /// id of the key that replaced this one, so a verifier can chain trust
+    /// forward from a retired key to its successor. `None` for a key that
+    /// hasn't been rotated out.
+    #[serde(default)]
+    pub superseded_by: Option<String>,
+    /// This signer's companion Ed25519 public key (chunk4-6), base64,
+    /// present when the entry was trusted as a hybrid identity. `None` for
+    /// an ML-DSA-87-only entry, preserving backward compatibility with
+    /// trust stores written before hybrid signing existed.
+    #[serde(default)]
+    pub ed_public_key: Option<String>,
+}
+
+/// Why and when a signer was actively distrusted (chunk4-2), as opposed to
+/// merely being absent from `entries` — a revoked key stays on record so
+/// "was this ever trusted, and why isn't it anymore" remains answerable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevocationEntry {
+    pub reason: String,
+    pub revoked_at: u64,
+}
+
+/// A key-rotation attestation: `self.sign(old_pk_hash || new_pk ||
+/// timestamp)` produced by the *old* (retiring) secret key, so a verifier
+/// holding only the old public key can confirm the new key is its
+/// legitimate successor rather than an attacker's substitute.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotationAttestation {
+    pub old_signer_id: String,
+    pub new_signer_id: String,
+    pub new_public_key: Vec<u8>,
+    pub timestamp: u64,
+    pub signature: Vec<u8>,
+}
+
+fn rotation_attestation_message(old_pk: &[u8], new_pk: &[u8], timestamp: u64) -> Vec<u8> {
+    let mut msg = blake3::hash(old_pk).as_bytes().to_vec();
+    msg.extend_from_slice(new_pk);
+    msg.extend_from_slice(&timestamp.to_be_bytes());
+    msg
+}
+
+/// Verify a [`RotationAttestation`] against the retiring signer's
+/// ML-DSA-87 public key.
+pub fn verify_rotation_attestation(attestation: &RotationAttestation, old_pk_bytes: &[u8]) -> Result<bool> {
+    let msg = rotation_attestation_message(old_pk_bytes, &attestation.new_public_key, attestation.timestamp);
+    verify_signature(&msg, &attestation.signature, old_pk_bytes)
+}
+
+/// A named group of signers plus the number of *distinct* ones that must
+/// each produce a valid signature over the same data before it's accepted
+/// — the TUF/update-framework model (e.g. "2 of these 3 maintainer keys
+/// must sign a release"), as opposed to the single-key trust `is_trusted`
+/// already provided.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Role {
+    pub signer_ids: Vec<String>,
+    pub threshold: usize,
+}
+
+/// A delegation (chunk5-3), TUF-style: trust decisions for any recipient
+/// label starting with `label_prefix` are deferred to the trust store at
+/// `trust_file` instead of this store's own `entries`/`revoked`. Lets one
+/// trust store hand off "anything under `release/`" to a different team's
+/// store without merging the two together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Delegation {
+    pub label_prefix: String,
+    pub trust_file: PathBuf,
+}
+
+/// Allow-list of trusted signer ids, plus named threshold roles over them,
+/// persisted as JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustStore {
+    pub entries: HashMap<String, TrustEntry>,
+    /// Named roles (see [`Role`]). Defaults to empty so a trust store
+    /// written before this field existed still loads unchanged.
+    #[serde(default)]
+    pub roles: HashMap<String, Role>,
+    /// Actively distrusted signers (chunk4-2), keyed by signer id. A
+    /// revoked id fails `is_trusted` even if its `entries` record is still
+    /// present — revocation is stronger than simple removal, since it
+    /// keeps the reason on file. Defaults to empty for backward
+    /// compatibility with trust stores written before this field existed.
+    #[serde(default)]
+    pub revoked: HashMap<String, RevocationEntry>,
+    /// Required quorum for each file class (chunk5-2), e.g. `"release"` ->
+    /// `2` meaning a release container needs 2 distinct trusted signers.
+    /// The store only records these (see [`Self::required_threshold`]);
+    /// `unseal_stream`'s quorum check takes its threshold from
+    /// `UnsealContext::min_valid_signers`, not from this map. Defaults to
+    /// empty so a trust store written before this
+    /// field existed still loads unchanged, and policy is opt-in per class.
+    #[serde(default)]
+    pub required_thresholds: HashMap<String, usize>,
+    /// Delegated sub-stores (chunk5-3), see [`Delegation`]. Defaults to
+    /// empty so a trust store written before this field existed still
+    /// loads unchanged.
+    #[serde(default)]
+    pub delegations: Vec<Delegation>,
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl TrustStore {
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(TrustStore::default());
+        }
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("reading trust store {}", path.display()))?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let data = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    /// True if `signer_id` is present in the store, not revoked, and, if
+    /// it carries an `expires_at`, that it hasn't passed yet.
+    pub fn is_trusted(&self, signer_id: &str) -> bool {
+        if self.revoked.contains_key(signer_id) {
+            return false;
+        }
+        match self.entries.get(signer_id) {
+            Some(entry) => entry.expires_at.map_or(true, |exp| unix_now() < exp),
+            None => false,
+        }
+    }
+
+    /// Actively distrust `signer_id`, recording why and when. The entry
+    /// (if any) stays in `entries` — revocation is a stronger, logged
+    /// statement than simply deleting the key — but `is_trusted` and
+    /// `verify_threshold` reject it from this point on.
+    pub fn revoke_signer(&mut self, signer_id: &str, reason: String) {
+        self.revoked.insert(
+            signer_id.to_string(),
+            RevocationEntry { reason, revoked_at: unix_now() },
+        );
+    }
+
+    /// Mark `old_id` as superseded by `new_id` (see `LocalSigner::rotate`).
+    /// A no-op if `old_id` has no entry in the store.
+    pub fn record_rotation(&mut self, old_id: &str, new_id: &str) {
+        if let Some(entry) = self.entries.get_mut(old_id) {
+            entry.superseded_by = Some(new_id.to_string());
+        }
+    }
+
+    /// True only when at least `role`'s `threshold` *distinct* signers who
+    /// are both in `role.signer_ids` and currently trusted (per
+    /// `is_trusted`) each produced a valid ML-DSA-87 signature over `data`
+    /// in `signatures`. A signer outside the role, not currently trusted,
+    /// or supplying an invalid signature simply doesn't count toward the
+    /// threshold — it doesn't fail the whole check.
+    pub fn verify_threshold(
+        &self,
+        role_name: &str,
+        data: &[u8],
+        signatures: &[(String, Vec<u8>)],
+    ) -> Result<bool> {
+        let role = match self.roles.get(role_name) {
+            Some(role) => role,
+            None => return Ok(false),
+        };
+
+        let mut satisfied: std::collections::HashSet<&str> = std::collections::HashSet::new();
+        for (signer_id, sig) in signatures {
+            if satisfied.contains(signer_id.as_str()) {
+                continue;
+            }
+            if !role.signer_ids.iter().any(|id| id == signer_id) || !self.is_trusted(signer_id) {
+                continue;
+            }
+            let Some(entry) = self.entries.get(signer_id) else { continue };
+            let pk_bytes = general_purpose::STANDARD
+                .decode(&entry.public_key_base64)
+                .with_context(|| format!("trust entry '{}' has invalid base64 public key", signer_id))?;
+            if matches!(verify_signature(data, sig, &pk_bytes), Ok(true)) {
+                satisfied.insert(signer_id.as_str());
+            }
+        }
+
+        Ok(satisfied.len() >= role.threshold)
+    }
+
+    /// Record that files of `file_class` require at least `threshold`
+    /// distinct trusted signers before they're accepted (chunk5-2).
+    pub fn set_required_threshold(&mut self, file_class: &str, threshold: usize) {
+        self.required_thresholds.insert(file_class.to_string(), threshold);
+    }
+
+    /// The configured quorum for `file_class`, if any was set.
+    pub fn required_threshold(&self, file_class: &str) -> Option<usize> {
+        self.required_thresholds.get(file_class).copied()
+    }
+
+    /// Delegate trust decisions for any label starting with `label_prefix`
+    /// to the store at `trust_file` (chunk5-3).
+    pub fn add_delegation(&mut self, label_prefix: &str, trust_file: impl Into<PathBuf>) {
+        self.delegations.push(Delegation { label_prefix: label_prefix.to_string(), trust_file: trust_file.into() });
+    }
+
+    /// Resolve trust for `signer_id`, consulting a delegated sub-store
+    /// (chunk5-3) if any of `labels` matches a configured delegation's
+    /// prefix, falling back to this store's own `is_trusted` otherwise. The
+    /// delegated store is loaded fresh on each call and checked directly —
+    /// delegation isn't chased transitively, so a delegated store's own
+    /// `delegations` are ignored.
+    pub fn is_trusted_for_labels(&self, signer_id: &str, labels: &[&str]) -> Result<bool> {
+        for label in labels {
+            if let Some(delegation) = self.delegations.iter().find(|d| label.starts_with(d.label_prefix.as_str())) {
+                let delegated = TrustStore::load_from_file(&delegation.trust_file)?;
+                return Ok(delegated.is_trusted(signer_id));
+            }
+        }
+        Ok(self.is_trusted(signer_id))
+    }
+}
+
+/// Count the *distinct* trusted signers across a header's primary signature
+/// and its [`crate::header::CoSignature`]s (chunk5-2), verifying every one
+/// rather than stopping at the first bad entry so a malicious partial set
+/// can't fool a quorum check, and deduping by signer id so the same key
+/// can't satisfy the threshold twice. Takes `trust_store` by reference
+/// (rather than loading it itself, as `unseal_stream` does) so quorum
+/// policy can be exercised against an in-memory trust store in tests.
+pub fn trusted_signer_set(
+    hdr: &crate::Header,
+    canonical_bytes: &[u8],
+    trust_store: &TrustStore,
+) -> Result<std::collections::HashSet<String>> {
+    let mut trusted = std::collections::HashSet::new();
+
+    if let Some(meta) = &hdr.signature_metadata {
+        if !hdr.ed25519_sig.is_empty()
+            && trust_store.is_trusted(&meta.signer_id)
+            && verify_hybrid_signature(
+                canonical_bytes,
+                &hdr.mldsa_sig,
+                &meta.public_key,
+                &hdr.ed25519_sig,
+                &meta.ed25519_public_key,
+            )?
+        {
+            trusted.insert(meta.signer_id.clone());
+        }
+    }
+
+    for co_sig in &hdr.co_signatures {
+        if trusted.contains(&co_sig.signer_id) || !trust_store.is_trusted(&co_sig.signer_id) {
+            continue;
+        }
+        if verify_hybrid_signature(
+            canonical_bytes,
+            &co_sig.signature,
+            &co_sig.public_key,
+            &co_sig.ed25519_signature,
+            &co_sig.ed25519_public_key,
+        )? {
+            trusted.insert(co_sig.signer_id.clone());
+        }
+    }
+
+    Ok(trusted)
+}
+
+/// Default location of the user's trust database.
+pub fn default_trustdb_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().context("could not determine home directory")?;
+    Ok(home.join(".qsfs").join("trustdb.json"))
+}
+
+/// Generate a fresh in-memory signer. Nothing is loaded from or written to
+/// disk, and `~/.qsfs/config` is not read.
+pub fn auto_provision_signer() -> Result<LocalSigner> {
+    Ok(LocalSigner::generate())
+}
+
+#[cfg(test)]
+mod encrypted_file_tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_an_encrypted_signer_file() {
+        let signer = LocalSigner::generate();
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("qsfs-signer-test-{}.bin", signer.id_hex()));
+
+        signer
+            .save_to_file_encrypted(&path, "correct horse battery staple", KdfParams::default(), AeadKind::Aes256Gcm)
+            .unwrap();
+        let loaded = LocalSigner::load_from_file_encrypted(&path, "correct horse battery staple").unwrap();
+
+        assert_eq!(loaded.id_hex(), signer.id_hex());
+        assert_eq!(loaded.public_key(), signer.public_key());
+        assert_eq!(loaded.ed25519_pk, signer.ed25519_pk);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_the_wrong_passphrase() {
+        let signer = LocalSigner::generate();
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("qsfs-signer-test-wrong-{}.bin", signer.id_hex()));
+
+        signer.save_to_file_encrypted(&path, "hunter2", KdfParams::default(), AeadKind::Aes256Gcm).unwrap();
+        let result = LocalSigner::load_from_file_encrypted(&path, "not hunter2");
+        assert!(result.is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn round_trips_every_kdf_and_aead_combination() {
+        for (kdf, aead) in [
+            (KdfKind::Argon2id, AeadKind::Aes256Gcm),
+            (KdfKind::Scrypt, AeadKind::Aes256Gcm),
+            (KdfKind::Pbkdf2Sha256, AeadKind::Aes256Gcm),
+            (KdfKind::Argon2id, AeadKind::ChaCha20Poly1305),
+        ] {
+            let signer = LocalSigner::generate();
+            let dir = std::env::temp_dir();
+            let path = dir.join(format!("qsfs-signer-test-combo-{}.bin", signer.id_hex()));
+
+            signer
+                .save_to_file_encrypted(&path, "correct horse battery staple", KdfParams::default_for(kdf), aead)
+                .unwrap();
+            let loaded = LocalSigner::load_from_file_encrypted(&path, "correct horse battery staple").unwrap();
+            assert_eq!(loaded.id_hex(), signer.id_hex());
+
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn config_defaults_to_argon2id_and_aes_gcm_when_absent() {
+        let config = SignerFileConfig::default();
+        assert_eq!(config.kdf_kind, KdfKind::Argon2id);
+        assert_eq!(config.aead_kind, AeadKind::Aes256Gcm);
+    }
+
+    #[test]
+    fn hybrid_signature_round_trips_and_verifies() {
+        let signer = LocalSigner::generate();
+        let msg = b"release v2.0.0";
+
+        let sig = signer.sign_hybrid(msg).unwrap();
+        let parsed = HybridSignature::parse(&sig.serialize()).unwrap();
+        assert_eq!(parsed, sig);
+        assert!(parsed.verify(msg, &signer.public_key(), signer.ed25519_pk.as_bytes()).unwrap());
+        assert!(!parsed.verify(b"tampered", &signer.public_key(), signer.ed25519_pk.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn hybrid_signer_id_differs_from_mldsa_only_id() {
+        let signer = LocalSigner::generate();
+        let id = hybrid_signer_id(&signer.public_key(), signer.ed25519_pk.as_bytes());
+        assert_ne!(id, signer.id_hex());
+    }
+
+    #[test]
+    fn json_keystore_round_trips_with_scrypt_and_pbkdf2() {
+        for kdf in [JsonKeystoreKdf::Scrypt { n: 1024, r: 8, p: 1 }, JsonKeystoreKdf::Pbkdf2 { c: 10_000 }] {
+            let signer = LocalSigner::generate();
+            let keystore = signer.to_json_keystore("correct horse battery staple", kdf).unwrap();
+
+            // Round-trips through JSON (de)serialization too, not just the struct.
+            let json = serde_json::to_string(&keystore).unwrap();
+            let reparsed: JsonKeystore = serde_json::from_str(&json).unwrap();
+
+            let loaded = LocalSigner::from_json_keystore(&reparsed, "correct horse battery staple").unwrap();
+            assert_eq!(loaded.id_hex(), signer.id_hex());
+            assert_eq!(loaded.public_key(), signer.public_key());
+        }
+    }
+
+    #[test]
+    fn json_keystore_rejects_wrong_passphrase_via_mac() {
+        let signer = LocalSigner::generate();
+        let keystore = signer.to_json_keystore("hunter2", JsonKeystoreKdf::Pbkdf2 { c: 10_000 }).unwrap();
+        let err = LocalSigner::from_json_keystore(&keystore, "not hunter2").unwrap_err();
+        assert!(err.to_string().contains("MAC mismatch"));
+    }
+}
+
+#[cfg(test)]
+mod trust_store_tests {
+    use super::*;
+
+    /// A label matching a delegation's prefix must defer trust entirely to
+    /// the delegated store (chunk5-3), even when the delegating store has
+    /// no entry for the signer at all.
+    #[test]
+    fn delegated_label_defers_to_the_delegated_store() {
+        let delegate_signer_id = "delegate-signer";
+        let mut delegated = TrustStore::default();
+        delegated.entries.insert(
+            delegate_signer_id.to_string(),
+            TrustEntry {
+                public_key_base64: "unused".to_string(),
+                note: "delegated team's key".to_string(),
+                added_at: 0,
+                expires_at: None,
+                roles: vec![],
+                superseded_by: None,
+                ed_public_key: None,
+            },
+        );
+        let dir = std::env::temp_dir();
+        let delegated_path = dir.join(format!("qsfs-delegated-trust-{}.json", delegate_signer_id));
+        delegated.save_to_file(&delegated_path).unwrap();
+
+        let mut root = TrustStore::default();
+        root.add_delegation("release/", &delegated_path);
+
+        assert!(root.is_trusted_for_labels(delegate_signer_id, &["release/v1.0"]).unwrap());
+        // A label outside the delegated prefix falls back to the root store,
+        // which has no entry for this signer.
+        assert!(!root.is_trusted_for_labels(delegate_signer_id, &["staging/v1.0"]).unwrap());
+
+        std::fs::remove_file(&delegated_path).unwrap();
+    }
+
+    /// With no matching delegation, `is_trusted_for_labels` behaves exactly
+    /// like plain `is_trusted`.
+    #[test]
+    fn falls_back_to_is_trusted_when_no_delegation_matches() {
+        let mut store = TrustStore::default();
+        store.entries.insert(
+            "local-signer".to_string(),
+            TrustEntry {
+                public_key_base64: "unused".to_string(),
+                note: "".to_string(),
+                added_at: 0,
+                expires_at: None,
+                roles: vec![],
+                superseded_by: None,
+                ed_public_key: None,
+            },
+        );
+        assert!(store.is_trusted_for_labels("local-signer", &["anything"]).unwrap());
+        assert!(!store.is_trusted_for_labels("someone-else", &["anything"]).unwrap());
+    }
+}
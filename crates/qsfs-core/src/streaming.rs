@@ -0,0 +1,445 @@
+use aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce as GcmNonce};
+use aes_gcm_siv::Aes256GcmSiv;
+use chacha20poly1305::ChaCha20Poly1305;
+use anyhow::{Result, bail};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use std::io::Write;
+use zeroize::Zeroize;
+
+use crate::security::hsm::{HsmKeyHandle, HsmSession};
+use crate::suite::AeadId;
+
+const MAX_CHUNK_SIZE: usize = 4 * 1024 * 1024; // 4 MiB max
+const MAX_CHUNKS: u64 = 1 << 32; // 4 billion chunks max
+
+/// Derive 96‑bit nonce from 64‑bit file_id seed and 32‑bit chunk counter.
+fn nonce_96(file_id: [u8;8], chunk_no: u32) -> [u8;12] {
+    let mut n = [0u8; 12];
+    n[..8].copy_from_slice(&file_id);
+    n[8..].copy_from_slice(&chunk_no.to_be_bytes());
+    n
+}
+
+/// The bulk AEAD, selected at runtime from the header's negotiated
+/// `SuiteId` rather than a compile-time feature — this is what lets a file
+/// declare AES-256-GCM-SIV, AES-256-GCM, or ChaCha20-Poly1305 without a
+/// format break (see `suite::SuiteId`).
+enum BulkAead {
+    Aes256Gcm(Aes256Gcm),
+    Aes256GcmSiv(Aes256GcmSiv),
+    ChaCha20Poly1305(ChaCha20Poly1305),
+}
+
+impl BulkAead {
+    fn new(aead_id: AeadId, key: &[u8; 32]) -> Self {
+        match aead_id {
+            AeadId::Aes256Gcm => BulkAead::Aes256Gcm(Aes256Gcm::new_from_slice(key).unwrap()),
+            AeadId::Aes256GcmSiv => BulkAead::Aes256GcmSiv(Aes256GcmSiv::new_from_slice(key).unwrap()),
+            AeadId::ChaCha20Poly1305 => {
+                BulkAead::ChaCha20Poly1305(ChaCha20Poly1305::new_from_slice(key).unwrap())
+            }
+        }
+    }
+
+    fn encrypt(&self, nonce: &[u8; 12], pt: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let n = GcmNonce::from_slice(nonce);
+        let payload = aead::Payload { msg: pt, aad };
+        let ct = match self {
+            BulkAead::Aes256Gcm(a) => a.encrypt(n, payload),
+            BulkAead::Aes256GcmSiv(a) => a.encrypt(n, payload),
+            BulkAead::ChaCha20Poly1305(a) => a.encrypt(n, payload),
+        };
+        ct.map_err(|_| anyhow::anyhow!("aead seal failed"))
+    }
+
+    fn decrypt(&self, nonce: &[u8; 12], ct: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let n = GcmNonce::from_slice(nonce);
+        let payload = aead::Payload { msg: ct, aad };
+        let pt = match self {
+            BulkAead::Aes256Gcm(a) => a.decrypt(n, payload),
+            BulkAead::Aes256GcmSiv(a) => a.decrypt(n, payload),
+            BulkAead::ChaCha20Poly1305(a) => a.decrypt(n, payload),
+        };
+        pt.map_err(|_| anyhow::anyhow!("aead tag failure"))
+    }
+}
+
+/// Where the per-chunk AEAD actually runs: against the in-memory key
+/// (`Software`, the default), or offloaded to an HSM key via the PKCS#11
+/// message-AEAD interface (`Hsm`, see `security::hsm`). Both produce the
+/// same wire framing, so a file sealed one way unseals identically the
+/// other way given the same raw key.
+pub enum ChunkCipher<'a> {
+    Software,
+    Hsm {
+        session: &'a HsmSession,
+        key: HsmKeyHandle,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nonce_uniqueness_within_file() {
+        let file_id = [1u8,2,3,4,5,6,7,8];
+        let n0 = nonce_96(file_id, 0);
+        let n1 = nonce_96(file_id, 1);
+        assert_ne!(n0, n1);
+    }
+
+    #[test]
+    fn test_nonce_uniqueness_across_files() {
+        let f1 = [1u8,2,3,4,5,6,7,8];
+        let f2 = [8u8,7,6,5,4,3,2,1];
+        let n_a = nonce_96(f1, 42);
+        let n_b = nonce_96(f2, 42);
+        assert_ne!(n_a, n_b);
+    }
+
+    #[tokio::test]
+    async fn encrypt_decrypt_round_trip_with_final_tag() {
+        let file_id = [1u8,2,3,4,5,6,7,8];
+        let key = [7u8; 32];
+        let aad = b"test-aad";
+        let plaintext = b"hello streaming world, spanning more than one chunk!".to_vec();
+
+        let mut pt_reader = plaintext.as_slice();
+        let mut ct = Vec::new();
+        encrypt_stream(&mut pt_reader, &mut ct, 8, file_id, aad, &key, AeadId::Aes256Gcm, ChunkCipher::Software, false)
+            .await
+            .unwrap();
+
+        let mut ct_reader = ct.as_slice();
+        let mut pt = Vec::new();
+        decrypt_stream(&mut ct_reader, &mut pt, file_id, aad, &key, AeadId::Aes256Gcm, ChunkCipher::Software, false)
+            .await
+            .unwrap();
+        assert_eq!(pt, plaintext);
+    }
+
+    #[tokio::test]
+    async fn encrypt_decrypt_round_trip_with_compression() {
+        let file_id = [1u8,2,3,4,5,6,7,8];
+        let key = [7u8; 32];
+        let aad = b"test-aad";
+        let plaintext = b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_vec();
+
+        let mut pt_reader = plaintext.as_slice();
+        let mut ct = Vec::new();
+        // One chunk: each chunk adds 13 bytes of framing plus a 16-byte tag,
+        // which would outweigh what zstd saves on 32-byte chunks.
+        encrypt_stream(&mut pt_reader, &mut ct, 1024, file_id, aad, &key, AeadId::Aes256Gcm, ChunkCipher::Software, true)
+            .await
+            .unwrap();
+        assert!(ct.len() < plaintext.len(), "highly compressible plaintext should shrink on the wire");
+
+        let mut ct_reader = ct.as_slice();
+        let mut pt = Vec::new();
+        decrypt_stream(&mut ct_reader, &mut pt, file_id, aad, &key, AeadId::Aes256Gcm, ChunkCipher::Software, true)
+            .await
+            .unwrap();
+        assert_eq!(pt, plaintext);
+    }
+
+    #[tokio::test]
+    async fn tampered_declared_uncompressed_length_fails_authentication() {
+        let file_id = [1u8,2,3,4,5,6,7,8];
+        let key = [7u8; 32];
+        let aad = b"test-aad";
+        let plaintext = b"short compressible payload".to_vec();
+
+        let mut pt_reader = plaintext.as_slice();
+        let mut ct = Vec::new();
+        encrypt_stream(&mut pt_reader, &mut ct, 64, file_id, aad, &key, AeadId::Aes256Gcm, ChunkCipher::Software, true)
+            .await
+            .unwrap();
+
+        // The declared uncompressed length sits right after chunk_no+tag
+        // (bytes 5..9) and is folded into the AAD, so flipping it in the clear
+        // must invalidate the AEAD tag rather than just changing what's
+        // decoded. Its low byte is flipped so the value stays under
+        // MAX_CHUNK_SIZE and reaches the AEAD check.
+        ct[8] ^= 0xff;
+
+        let mut ct_reader = ct.as_slice();
+        let mut pt = Vec::new();
+        let err = decrypt_stream(&mut ct_reader, &mut pt, file_id, aad, &key, AeadId::Aes256Gcm, ChunkCipher::Software, true)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("aead tag failure"));
+    }
+
+    #[tokio::test]
+    async fn truncated_stream_without_final_tag_is_rejected() {
+        let file_id = [1u8,2,3,4,5,6,7,8];
+        let key = [7u8; 32];
+        let aad = b"test-aad";
+        let plaintext = b"hello streaming world, spanning more than one chunk!".to_vec();
+
+        let mut pt_reader = plaintext.as_slice();
+        let mut ct = Vec::new();
+        encrypt_stream(&mut pt_reader, &mut ct, 8, file_id, aad, &key, AeadId::Aes256Gcm, ChunkCipher::Software, false)
+            .await
+            .unwrap();
+
+        // Drop the last frame (which carries TAG_FINAL), simulating an
+        // attacker truncating the container. Walk the frames to find where
+        // the last one starts, since frame lengths vary chunk to chunk.
+        let mut offsets = vec![0usize];
+        let mut pos = 0usize;
+        while pos < ct.len() {
+            let len = u32::from_be_bytes(ct[pos + 5..pos + 9].try_into().unwrap()) as usize;
+            pos += 9 + len;
+            if pos < ct.len() { offsets.push(pos); }
+        }
+        let mut truncated_reader = &ct[..*offsets.last().unwrap()];
+
+        let mut pt = Vec::new();
+        let err = decrypt_stream(&mut truncated_reader, &mut pt, file_id, aad, &key, AeadId::Aes256Gcm, ChunkCipher::Software, false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("truncated"));
+    }
+}
+
+/// STREAM-style (libsodium secretstream / RFC 8188) chunk tags, folded into
+/// every chunk's AEAD associated data so the ciphertext itself authenticates
+/// whether more chunks follow. Without this an attacker can truncate the
+/// frame stream at any chunk boundary and decryption still "succeeds" on the
+/// truncated prefix.
+const TAG_MORE: u8 = 0x00;
+const TAG_FINAL: u8 = 0x01;
+
+/// Fold `chunk_no`, the STREAM tag, and (when compression is on) the
+/// chunk's declared uncompressed length into the per-chunk AAD, so none of
+/// them can be swapped in the clear frame header without also invalidating
+/// the AEAD tag. `uncompressed_len` lets the decrypter size its
+/// decompression buffer exactly rather than guessing or over-allocating.
+fn per_chunk_aad(aad: &[u8], chunk_no: u32, tag: u8, uncompressed_len: Option<u32>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(aad.len() + 5 + 4);
+    out.extend_from_slice(aad);
+    out.extend_from_slice(&chunk_no.to_be_bytes());
+    out.push(tag);
+    if let Some(len) = uncompressed_len {
+        out.extend_from_slice(&len.to_be_bytes());
+    }
+    out
+}
+
+/// Compress `pt` with zstd ahead of AEAD sealing (chunk2-5). Compression is
+/// opt-in (`Header::compression`) and must never be turned on for content
+/// where a secret mixed into the plaintext alongside attacker-influenced
+/// bytes would open a CRIME/BREACH-style compression-ratio side channel —
+/// that tradeoff is the caller's to make, not this function's.
+fn compress_chunk(pt: &[u8]) -> Result<Vec<u8>> {
+    zstd::bulk::compress(pt, 0).map_err(|e| anyhow::anyhow!("chunk compression failed: {e}"))
+}
+
+/// Decompress a chunk to exactly `uncompressed_len` bytes, the length
+/// carried (and authenticated, see `per_chunk_aad`) in the frame header.
+/// Bounding the output buffer to this declared length — rather than
+/// growing unboundedly — keeps a tampered or malicious declared length
+/// from turning into a decompression bomb.
+fn decompress_chunk(ct: &[u8], uncompressed_len: u32) -> Result<Vec<u8>> {
+    let pt = zstd::bulk::decompress(ct, uncompressed_len as usize)
+        .map_err(|e| anyhow::anyhow!("chunk decompression failed: {e}"))?;
+    if pt.len() != uncompressed_len as usize {
+        bail!("decompressed chunk length {} does not match declared length {}", pt.len(), uncompressed_len);
+    }
+    Ok(pt)
+}
+
+/// Encrypt in streaming mode, sealing each chunk under the suite's bulk AEAD.
+/// `reader` supplies the plaintext — a file, an in-memory byte slice, or any
+/// other `AsyncRead` — so callers aren't tied to reading from disk.
+///
+/// Frames as `[u32 chunk_no][u8 tag][u32 uncompressed_len]?[u32 len]
+/// [ciphertext]` — the `uncompressed_len` field is only present when
+/// `compress` is set — one chunk ahead of `reader` at a time so the last
+/// chunk can be marked `TAG_FINAL` in its AAD before EOF rather than
+/// relying on EOF alone (see `TAG_FINAL`).
+#[allow(unused_variables)]
+pub async fn encrypt_stream(
+    reader: &mut (impl AsyncRead + Unpin),
+    out: &mut impl Write,
+    chunk_size: usize,
+    file_id: [u8;8],
+    aad: &[u8],
+    k1_aes: &[u8;32],
+    aead_id: AeadId,
+    chunk_cipher: ChunkCipher<'_>,
+    compress: bool,
+) -> Result<()> {
+    // Validate chunk size
+    if chunk_size > MAX_CHUNK_SIZE {
+        bail!("Chunk size too large: {} > {}", chunk_size, MAX_CHUNK_SIZE);
+    }
+    if matches!(chunk_cipher, ChunkCipher::Hsm { .. }) && !matches!(aead_id, AeadId::Aes256Gcm) {
+        bail!("HSM message-AEAD offload only supports AES-256-GCM, got {:?}", aead_id);
+    }
+
+    let aead = BulkAead::new(aead_id, k1_aes);
+
+    let mut cur = vec![0u8; chunk_size];
+    let mut cur_len = reader.read(&mut cur).await?;
+    let mut chunk_no: u32 = 0;
+
+    loop {
+        if chunk_no as u64 >= MAX_CHUNKS {
+            bail!("Too many chunks: {} >= {}", chunk_no, MAX_CHUNKS);
+        }
+
+        // One-chunk lookahead: whether *this* chunk is final depends on
+        // whether there's anything after it.
+        let mut next = vec![0u8; chunk_size];
+        let next_len = reader.read(&mut next).await?;
+        let tag = if next_len == 0 { TAG_FINAL } else { TAG_MORE };
+
+        let n96 = nonce_96(file_id, chunk_no);
+        let raw_pt = &cur[..cur_len];
+        let (payload, uncompressed_len) = if compress {
+            (compress_chunk(raw_pt)?, Some(raw_pt.len() as u32))
+        } else {
+            (raw_pt.to_vec(), None)
+        };
+        let chunk_aad = per_chunk_aad(aad, chunk_no, tag, uncompressed_len);
+
+        let ct_outer = match &chunk_cipher {
+            ChunkCipher::Software => aead.encrypt(&n96, &payload, &chunk_aad)?,
+            ChunkCipher::Hsm { session, key } => session.encrypt_chunk(*key, &n96, &chunk_aad, &payload)?,
+        };
+
+        out.write_all(&chunk_no.to_be_bytes())?;
+        out.write_all(&[tag])?;
+        if let Some(len) = uncompressed_len {
+            out.write_all(&len.to_be_bytes())?;
+        }
+        out.write_all(&(ct_outer.len() as u32).to_be_bytes())?;
+        out.write_all(&ct_outer)?;
+
+        cur[..cur_len].zeroize();
+
+        if tag == TAG_FINAL {
+            break;
+        }
+
+        chunk_no = chunk_no.checked_add(1).ok_or_else(|| anyhow::anyhow!("chunk overflow"))?;
+        cur = next;
+        cur_len = next_len;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// Decrypt counterpart, unsealing each chunk under the suite's bulk AEAD.
+/// `out` is any `Write` sink — a file, an in-memory `Vec<u8>`, or similar.
+///
+/// Requires observing a chunk authenticated with `TAG_FINAL` before EOF
+/// (otherwise the stream was truncated) and rejects any further frames
+/// after one, closing the truncation/trailing-data gap plain EOF-based
+/// framing leaves open.
+#[allow(unused_variables)]
+pub async fn decrypt_stream(
+    in_bytes: &mut (impl AsyncRead + Unpin),
+    out: &mut impl Write,
+    file_id: [u8;8],
+    aad: &[u8],
+    k1_aes: &[u8;32],
+    aead_id: AeadId,
+    chunk_cipher: ChunkCipher<'_>,
+    compress: bool,
+) -> Result<()> {
+    if matches!(chunk_cipher, ChunkCipher::Hsm { .. }) && !matches!(aead_id, AeadId::Aes256Gcm) {
+        bail!("HSM message-AEAD offload only supports AES-256-GCM, got {:?}", aead_id);
+    }
+
+    let aead = BulkAead::new(aead_id, k1_aes);
+
+    let mut expected_chunk: u32 = 0;
+    let mut total_chunks: u64 = 0;
+    let mut saw_final = false;
+
+    loop {
+        let mut hdr = [0u8; 5];
+        match in_bytes.read_exact(&mut hdr).await {
+            Ok(_) => {},
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        }
+
+        if saw_final {
+            bail!("chunk data present after authenticated final chunk");
+        }
+
+        let chunk_no = u32::from_be_bytes([hdr[0], hdr[1], hdr[2], hdr[3]]);
+        let tag = hdr[4];
+
+        let uncompressed_len = if compress {
+            let mut buf = [0u8; 4];
+            in_bytes.read_exact(&mut buf).await?;
+            let len = u32::from_be_bytes(buf);
+            if len as usize > MAX_CHUNK_SIZE {
+                bail!("declared uncompressed chunk length too large: {}", len);
+            }
+            Some(len)
+        } else {
+            None
+        };
+
+        let mut len_buf = [0u8; 4];
+        in_bytes.read_exact(&mut len_buf).await?;
+        let len = u32::from_be_bytes(len_buf) as usize;
+
+        // Validate chunk ordering, tag, and limits
+        if tag != TAG_MORE && tag != TAG_FINAL {
+            bail!("invalid chunk tag: {}", tag);
+        }
+        if chunk_no != expected_chunk {
+            bail!("chunk out of order: expected {}, got {}", expected_chunk, chunk_no);
+        }
+        if len > MAX_CHUNK_SIZE + 16 { // +16 for AEAD tag
+            bail!("chunk too large: {}", len);
+        }
+        if total_chunks >= MAX_CHUNKS {
+            bail!("too many chunks: {}", total_chunks);
+        }
+
+        let mut ct = vec![0u8; len];
+        in_bytes.read_exact(&mut ct).await?;
+
+        let n96 = nonce_96(file_id, chunk_no);
+        let chunk_aad = per_chunk_aad(aad, chunk_no, tag, uncompressed_len);
+        let payload = match &chunk_cipher {
+            ChunkCipher::Software => aead.decrypt(&n96, &ct, &chunk_aad),
+            ChunkCipher::Hsm { session, key } => session.decrypt_chunk(*key, &n96, &chunk_aad, &ct),
+        }
+        .map_err(|_| anyhow::anyhow!("aead tag failure at chunk {}", chunk_no))?;
+
+        let pt = match uncompressed_len {
+            Some(declared) => decompress_chunk(&payload, declared)?,
+            None => payload,
+        };
+        out.write_all(&pt)?;
+
+        if tag == TAG_FINAL {
+            saw_final = true;
+        }
+
+        expected_chunk = expected_chunk.checked_add(1).ok_or_else(|| anyhow::anyhow!("chunk overflow"))?;
+        total_chunks += 1;
+
+        // Zeroize sensitive data
+        ct.zeroize();
+    }
+
+    if !saw_final {
+        bail!("stream truncated: no authenticated final chunk observed");
+    }
+
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,183 @@
+//! Ciphersuite identifiers.
+//!
+//! Modeled on RFC 9180's HPKE ciphersuites: a file negotiates a (KEM, KDF,
+//! AEAD) triple rather than a bare AEAD choice. `SuiteId` is the on-the-wire
+//! identifier stored in `Header::suite`; each variant fixes all three
+//! components so adding a new combination is a new variant, not a format
+//! break.
+
+use serde::{Deserialize, Serialize};
+
+/// The hybrid KEM used to wrap the per-recipient DEK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KemId {
+    /// ML-KEM-1024 combined with X25519 (hybrid, the only KEM offered today).
+    MlKem1024X25519,
+}
+
+/// The KDF used to derive the KEK/content keys from the KEM shared secret(s).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KdfId {
+    HkdfSha3_384,
+    HkdfSha512,
+}
+
+/// The bulk AEAD used to seal chunks (and, today, to wrap the DEK).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AeadId {
+    Aes256Gcm,
+    Aes256GcmSiv,
+    ChaCha20Poly1305,
+}
+
+/// Optional pre-encryption compression applied to each chunk's plaintext
+/// before AEAD sealing (chunk2-5, see `streaming::encrypt_stream`). Stored
+/// in `Header::compression`; `None` (the default) reproduces the
+/// uncompressed pre-chunk2-5 layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompressionId {
+    Zstd,
+}
+
+impl CompressionId {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CompressionId::Zstd => "zstd",
+        }
+    }
+}
+
+impl KemId {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KemId::MlKem1024X25519 => "mlkem1024x25519",
+        }
+    }
+}
+
+impl KdfId {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KdfId::HkdfSha3_384 => "hkdf-sha3-384",
+            KdfId::HkdfSha512 => "hkdf-sha512",
+        }
+    }
+}
+
+impl AeadId {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AeadId::Aes256Gcm => "aes256-gcm",
+            AeadId::Aes256GcmSiv => "aes256-gcm-siv",
+            AeadId::ChaCha20Poly1305 => "chacha20-poly1305",
+        }
+    }
+}
+
+/// Structured (KEM, KDF, AEAD) ciphersuite identifier.
+///
+/// The variant names are kept stable for wire/back-compat: `Aes256GcmSiv`
+/// and `Aes256Gcm` are the pre-existing identifiers (hybrid KEM +
+/// HKDF-SHA3-384 implied), `MlKem1024X25519HkdfSha512ChaCha20Poly1305` is a
+/// fully alternate triple demonstrating the new agility.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SuiteId {
+    /// ML-KEM-1024+X25519 / HKDF-SHA3-384 / AES-256-GCM-SIV (default).
+    Aes256GcmSiv,
+    /// ML-KEM-1024+X25519 / HKDF-SHA3-384 / AES-256-GCM.
+    Aes256Gcm,
+    /// ML-KEM-1024+X25519 / HKDF-SHA512 / ChaCha20-Poly1305.
+    MlKem1024X25519HkdfSha512ChaCha20Poly1305,
+}
+
+impl SuiteId {
+    /// The suite used when a file doesn't otherwise negotiate one.
+    pub fn current() -> Self {
+        SuiteId::Aes256GcmSiv
+    }
+
+    pub fn kem(self) -> KemId {
+        match self {
+            SuiteId::Aes256GcmSiv
+            | SuiteId::Aes256Gcm
+            | SuiteId::MlKem1024X25519HkdfSha512ChaCha20Poly1305 => KemId::MlKem1024X25519,
+        }
+    }
+
+    pub fn kdf(self) -> KdfId {
+        match self {
+            SuiteId::Aes256GcmSiv | SuiteId::Aes256Gcm => KdfId::HkdfSha3_384,
+            SuiteId::MlKem1024X25519HkdfSha512ChaCha20Poly1305 => KdfId::HkdfSha512,
+        }
+    }
+
+    pub fn aead(self) -> AeadId {
+        match self {
+            SuiteId::Aes256GcmSiv => AeadId::Aes256GcmSiv,
+            SuiteId::Aes256Gcm => AeadId::Aes256Gcm,
+            SuiteId::MlKem1024X25519HkdfSha512ChaCha20Poly1305 => AeadId::ChaCha20Poly1305,
+        }
+    }
+
+    /// Wire/AAD name of the bulk AEAD alone, unchanged from the pre-agility
+    /// format so existing `pae_v2_*` vectors stay valid.
+    pub fn as_str(self) -> &'static str {
+        self.aead().as_str()
+    }
+
+    /// Full "kem/kdf/aead" descriptor. `CanonicalHeader::serialize` (see
+    /// `canonical`) binds this whole triple into the `params:` line it
+    /// signs, so substituting any one component fails signature
+    /// verification — the per-chunk bulk-stream AAD (`pae::pae_v2_compat`)
+    /// only binds the bare AEAD name via [`Self::as_str`], not this.
+    pub fn full_descriptor(self) -> String {
+        format!("{}/{}/{}", self.kem().as_str(), self.kdf().as_str(), self.aead().as_str())
+    }
+
+    /// Inverse of [`Self::full_descriptor`]: recover the `SuiteId` whose
+    /// (KEM, KDF, AEAD) triple matches `descriptor` exactly. Used by
+    /// `CanonicalHeader::parse` (chunk3-6) to reconstruct a `Header` from
+    /// its canonical-bytes `params:` line.
+    pub fn from_full_descriptor(descriptor: &str) -> Result<Self, String> {
+        for suite in [
+            SuiteId::Aes256GcmSiv,
+            SuiteId::Aes256Gcm,
+            SuiteId::MlKem1024X25519HkdfSha512ChaCha20Poly1305,
+        ] {
+            if suite.full_descriptor() == descriptor {
+                return Ok(suite);
+            }
+        }
+        Err(format!("unrecognized suite descriptor '{}'", descriptor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_suite_is_gcm_siv() {
+        assert_eq!(SuiteId::current(), SuiteId::Aes256GcmSiv);
+        assert_eq!(SuiteId::current().as_str(), "aes256-gcm-siv");
+    }
+
+    #[test]
+    fn full_descriptor_covers_all_three_components() {
+        let d = SuiteId::MlKem1024X25519HkdfSha512ChaCha20Poly1305.full_descriptor();
+        assert_eq!(d, "mlkem1024x25519/hkdf-sha512/chacha20-poly1305");
+    }
+
+    #[test]
+    fn from_full_descriptor_round_trips_every_suite() {
+        for suite in [
+            SuiteId::Aes256GcmSiv,
+            SuiteId::Aes256Gcm,
+            SuiteId::MlKem1024X25519HkdfSha512ChaCha20Poly1305,
+        ] {
+            let descriptor = suite.full_descriptor();
+            assert_eq!(SuiteId::from_full_descriptor(&descriptor).unwrap(), suite);
+        }
+        assert!(SuiteId::from_full_descriptor("bogus/descriptor/here").is_err());
+    }
+}
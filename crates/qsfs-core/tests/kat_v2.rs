@@ -6,7 +6,8 @@ use hex_literal::hex;
 use qsfs_core::pae::pae_v2_compat;
 use qsfs_core::suite::SuiteId;
 use qsfs_core::Header;
-use qsfs_core::derivation::derive_kek;
+use qsfs_core::derivation::{derive_kek, KemTranscript};
+use qsfs_core::suite::KdfId;
 
 #[test]
 fn kat_pae_bytes() {
@@ -20,11 +21,15 @@ fn kat_pae_bytes() {
         blake3_of_plain: [0u8; 32],
         suite: SuiteId::Aes256GcmSiv,
         kdf_salt: None, // v2.0 layout
+        compression: None,
         recipients: vec![],
+        passphrase_recipients: vec![],
         eph_x25519_pk: [0u8;32],
         mldsa_sig: vec![],
         ed25519_sig: vec![],
         signature_metadata: None,
+        co_signatures: Vec::new(),
+        manifest: None,
         fin: 1,
     };
     let aad = pae_v2_compat(&hdr);
@@ -44,17 +49,24 @@ fn kat_pae_bytes() {
 
 #[test]
 fn kat_kek_and_wrap() {
-    // KEK derivation inputs
+    // KEK derivation inputs. mlkem_ct/eph_x25519_pk/recipient_x25519_pk are
+    // left empty here to isolate the shared-secret binding; chunk0-2 covers
+    // the full-transcript case end to end via seal/unseal.
     let mlkem_ss = hex!(
         "303132333435363738393a3b3c3d3e3f404142434445464748494a4b4c4d4e4f"
     );
     let x25519_ss = hex!(
         "505152535455565758595a5b5c5d5e5f606162636465666768696a6b6c6d6e6f"
     );
-    let kek = derive_kek(&mlkem_ss, &x25519_ss, None);
-    let kek_expected = hex!(
-        "b48776ae06e112d1115e002a687cb49b692e585eb37edb36e9ae3b2e1ddcee12"
-    );
+    let transcript = KemTranscript {
+        mlkem_ss: &mlkem_ss,
+        x25519_ss: &x25519_ss,
+        mlkem_ct: &[],
+        eph_x25519_pk: &[],
+        recipient_x25519_pk: &[],
+    };
+    let kek = derive_kek(&transcript, None, KdfId::HkdfSha3_384);
+    let kek_expected = hex!("a8d2123649575b82282f904b0b0595a5b55d23fa2a7b2cfa241b2e89d76c52cd");
     assert_eq!(kek, kek_expected, "KEK mismatch");
 
     // CEK wrap under AES-256-GCM with fixed nonce
@@ -67,8 +79,8 @@ fn kat_kek_and_wrap() {
         .encrypt(GcmNonce::from_slice(&nonce), cek.as_slice())
         .expect("wrap cek");
     let ct_expected = hex!(
-        "d0e68aa6ff9640c38b95c05c35314c53a3273536904bf2463ea70edb7ddcf229"
-        "4890bdc7ccb2d1026d85c49e8d52d505"
+        "7c68822db8f970ee20e36fed4763ba6020d62419b17fca37c6c42a6d7275eb0"
+        "ed96d0022906896b838d332e1d71f2e90"
     );
     assert_eq!(ct, ct_expected, "wrapped CEK mismatch");
 }
@@ -87,11 +99,15 @@ fn kat_chunk0_gcm_siv() {
         blake3_of_plain: [0u8; 32],
         suite: SuiteId::Aes256GcmSiv,
         kdf_salt: None,
+        compression: None,
         recipients: vec![],
+        passphrase_recipients: vec![],
         eph_x25519_pk: [0u8;32],
         mldsa_sig: vec![],
         ed25519_sig: vec![],
         signature_metadata: None,
+        co_signatures: Vec::new(),
+        manifest: None,
         fin: 1,
     };
     let aad = pae_v2_compat(&hdr);
@@ -2,6 +2,8 @@
 #[cfg(feature="gcm-siv")] use aes_gcm_siv::{Aes256GcmSiv, aead::Aead, KeyInit, Nonce as N12};
 
 use qsfs_core::{suite::SuiteId, pae::pae_v2_compat};
+use qsfs_core::suite::KdfId;
+use qsfs_core::derivation::{derive_kek, KemTranscript};
 use qsfs_core::Header;
 
 fn header_for_tests(suite: SuiteId) -> Header {
@@ -12,11 +14,15 @@ fn header_for_tests(suite: SuiteId) -> Header {
         blake3_of_plain: [0u8; 32],
         suite,
         kdf_salt: None,
+        compression: None,
         recipients: vec![],
+        passphrase_recipients: vec![],
         eph_x25519_pk: [0u8;32],
         mldsa_sig: vec![],
         ed25519_sig: vec![],
         signature_metadata: None,
+        co_signatures: Vec::new(),
+        manifest: None,
         fin: 1,
     }
 }
@@ -59,6 +65,40 @@ fn nonce_reuse_under_gcm_leaks_xor_relation() {
     assert_eq!(xr_ct, xr_pt, "GCM nonce reuse reveals XOR(m1,m2)");
 }
 
+#[test]
+fn kek_binds_full_kem_transcript() {
+    // The X-Wing-style combiner must commit to ct/eph_x25519_pk/recipient
+    // pubkey, not just the raw shared secrets: substituting any of them
+    // (e.g. a re-encapsulated ciphertext that happens to decapsulate to the
+    // same secret) must change the derived KEK.
+    let mlkem_ss = [1u8; 32];
+    let x25519_ss = [2u8; 32];
+    let mlkem_ct = [3u8; 8];
+    let eph_x25519_pk = [4u8; 32];
+    let recipient_x25519_pk = [5u8; 32];
+
+    let base = KemTranscript {
+        mlkem_ss: &mlkem_ss,
+        x25519_ss: &x25519_ss,
+        mlkem_ct: &mlkem_ct,
+        eph_x25519_pk: &eph_x25519_pk,
+        recipient_x25519_pk: &recipient_x25519_pk,
+    };
+    let kek_base = derive_kek(&base, None, KdfId::HkdfSha3_384);
+
+    let other_ct = [9u8; 8];
+    let substituted_ct = KemTranscript { mlkem_ct: &other_ct, ..base };
+    assert_ne!(kek_base, derive_kek(&substituted_ct, None, KdfId::HkdfSha3_384));
+
+    let other_eph = [9u8; 32];
+    let substituted_eph = KemTranscript { eph_x25519_pk: &other_eph, ..base };
+    assert_ne!(kek_base, derive_kek(&substituted_eph, None, KdfId::HkdfSha3_384));
+
+    let other_recip = [9u8; 32];
+    let substituted_recip = KemTranscript { recipient_x25519_pk: &other_recip, ..base };
+    assert_ne!(kek_base, derive_kek(&substituted_recip, None, KdfId::HkdfSha3_384));
+}
+
 #[test]
 fn wrap_tamper_fails() {
     use aes_gcm::{Aes256Gcm, aead::Aead as _, KeyInit};
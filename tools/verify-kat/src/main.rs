@@ -1,10 +1,14 @@
 use aead::{Aead, KeyInit};
 use aes_gcm::{Aes256Gcm, Nonce as N12};
+use aes_gcm_siv::{Aes256GcmSiv, Nonce as SivNonce};
+use chacha20poly1305::ChaCha20Poly1305;
 use hkdf::Hkdf;
-use serde::Deserialize;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
 use sha3::Sha3_384;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 #[allow(dead_code)]
 struct Kat {
     version: String,
@@ -16,9 +20,15 @@ struct Kat {
     hkdf: HkdfKat,
     wrap: WrapKat,
     chunk0_siv: ChunkSivKat,
+    /// The stream key (`k1`) `chunk0_siv.ct_hex` was sealed under. Without
+    /// this the SIV section was unverifiable — it only documented *a*
+    /// ciphertext, not one this tool could recompute and compare.
+    /// `generate` always fills it in; hand-authored KATs that predate this
+    /// field leave it `None`, and `verify` just skips that section.
+    stream_k1_hex: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct HkdfKat {
     hash: String,
     extract_salt: String,
@@ -28,7 +38,7 @@ struct HkdfKat {
     kek_hex: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct WrapKat {
     alg: String,
     nonce_hex: String,
@@ -36,7 +46,7 @@ struct WrapKat {
     wrapped_hex: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 #[allow(dead_code)]
 struct ChunkSivKat {
     pt_utf8: String,
@@ -51,30 +61,102 @@ fn hex_to<const N: usize>(s: &str) -> [u8; N] {
     out
 }
 
-fn main() -> anyhow::Result<()> {
-    let path = std::env::args().nth(1).expect("usage: verify-kat file.json");
-    let data = std::fs::read_to_string(path)?;
-    let kat: Kat = serde_json::from_str(&data)?;
+/// The AEAD/KDF pairing each `SuiteId` variant fixes (mirroring
+/// `qsfs_core::suite::SuiteId`, reproduced by hand here so this stays a
+/// cross-implementation check rather than a call into the library under
+/// test). `Suite::as_str()` matches `SuiteId::as_str()`, the bare AEAD
+/// wire name already used as the `suite` field's value.
+#[derive(Clone, Copy)]
+enum Suite {
+    Aes256GcmSiv,
+    Aes256Gcm,
+    MlKem1024X25519HkdfSha512ChaCha20Poly1305,
+}
 
-    // Rebuild AAD per spec
-    let aad_expected = hex::decode(&kat.aad_hex)?;
-    let suite_ascii = kat.suite.as_bytes();
+impl Suite {
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Ok(match s {
+            "aes256-gcm-siv" => Suite::Aes256GcmSiv,
+            "aes256-gcm" => Suite::Aes256Gcm,
+            "chacha20-poly1305" => Suite::MlKem1024X25519HkdfSha512ChaCha20Poly1305,
+            other => anyhow::bail!(
+                "unknown suite '{}' (expected aes256-gcm-siv, aes256-gcm, or chacha20-poly1305)",
+                other
+            ),
+        })
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Suite::Aes256GcmSiv => "aes256-gcm-siv",
+            Suite::Aes256Gcm => "aes256-gcm",
+            Suite::MlKem1024X25519HkdfSha512ChaCha20Poly1305 => "chacha20-poly1305",
+        }
+    }
+
+    /// Every suite but the alternate ChaCha20-Poly1305 one uses
+    /// HKDF-SHA3-384; that one uses HKDF-SHA512 (see `suite.rs`'s `KdfId`).
+    fn hkdf_hash_name(self) -> &'static str {
+        match self {
+            Suite::Aes256GcmSiv | Suite::Aes256Gcm => "sha3-384",
+            Suite::MlKem1024X25519HkdfSha512ChaCha20Poly1305 => "sha512",
+        }
+    }
+
+    /// Seal `pt` under `k1` with `nonce`/`aad`, dispatching to this
+    /// suite's bulk AEAD — the chunk-0 encryption the `chunk0_siv` section
+    /// documents, generalized beyond GCM-SIV so every suite can emit one.
+    fn seal_chunk0(self, k1: &[u8; 32], nonce: &[u8; 12], pt: &[u8], aad: &[u8]) -> Vec<u8> {
+        let payload = aead::Payload { msg: pt, aad };
+        match self {
+            Suite::Aes256GcmSiv => {
+                let c = Aes256GcmSiv::new_from_slice(k1).expect("key");
+                c.encrypt(SivNonce::from_slice(nonce), payload).expect("seal")
+            }
+            Suite::Aes256Gcm => {
+                let c = Aes256Gcm::new_from_slice(k1).expect("key");
+                c.encrypt(N12::from_slice(nonce), payload).expect("seal")
+            }
+            Suite::MlKem1024X25519HkdfSha512ChaCha20Poly1305 => {
+                let c = ChaCha20Poly1305::new_from_slice(k1).expect("key");
+                c.encrypt(chacha20poly1305::Nonce::from_slice(nonce), payload).expect("seal")
+            }
+        }
+    }
+}
+
+fn rebuild_aad(suite: &str, chunk_size: u32, file_id_hex: &str, kdf_salt_hex: Option<&str>) -> anyhow::Result<Vec<u8>> {
     let mut aad = Vec::new();
-    let prefix = if kat.kdf_salt_hex.is_some() { b"QSFS-PAE\x02" } else { b"QSFS-PAE\x01" };
+    let prefix = if kdf_salt_hex.is_some() { b"QSFS-PAE\x02" } else { b"QSFS-PAE\x01" };
     aad.extend_from_slice(prefix);
     let mut items: Vec<Vec<u8>> = vec![
         b"qsfs/v2".to_vec(),
-        suite_ascii.to_vec(),
-        kat.chunk_size.to_be_bytes().to_vec(),
-        hex::decode(&kat.file_id_hex)?,
+        suite.as_bytes().to_vec(),
+        chunk_size.to_be_bytes().to_vec(),
+        hex::decode(file_id_hex)?,
     ];
-    if let Some(s) = &kat.kdf_salt_hex { items.push(hex::decode(s)?); }
-    for it in items { aad.extend_from_slice(&(it.len() as u64).to_be_bytes()); aad.extend_from_slice(&it); }
-    if aad != aad_expected { anyhow::bail!("AAD mismatch"); }
+    if let Some(s) = kdf_salt_hex {
+        items.push(hex::decode(s)?);
+    }
+    for it in items {
+        aad.extend_from_slice(&(it.len() as u64).to_be_bytes());
+        aad.extend_from_slice(&it);
+    }
+    Ok(aad)
+}
+
+fn verify(path: &str) -> anyhow::Result<()> {
+    let data = std::fs::read_to_string(path)?;
+    let kat: Kat = serde_json::from_str(&data)?;
 
-    // HKDF (SHA3-384)
-    assert_eq!(kat.hkdf.hash, "sha3-384");
-    assert_eq!(kat.hkdf.info, "qsfs/kek/v2");
+    // Rebuild AAD per spec
+    let aad_expected = hex::decode(&kat.aad_hex)?;
+    let aad = rebuild_aad(&kat.suite, kat.chunk_size, &kat.file_id_hex, kat.kdf_salt_hex.as_deref())?;
+    if aad != aad_expected {
+        anyhow::bail!("AAD mismatch");
+    }
+
+    // HKDF
     let mut ikm = hex::decode(&kat.hkdf.mlkem_ss_hex)?;
     let x = hex::decode(&kat.hkdf.x25519_ss_hex).unwrap_or_default();
     ikm.extend_from_slice(&x);
@@ -83,13 +165,24 @@ fn main() -> anyhow::Result<()> {
     } else {
         kat.hkdf.extract_salt.as_bytes().to_vec()
     };
-    let hk = Hkdf::<Sha3_384>::new(Some(&salt_bytes), &ikm);
     let mut kek = [0u8; 32];
-    hk.expand(b"qsfs/kek/v2", &mut kek).expect("expand");
+    match kat.hkdf.hash.as_str() {
+        "sha3-384" => {
+            let hk = Hkdf::<Sha3_384>::new(Some(&salt_bytes), &ikm);
+            hk.expand(kat.hkdf.info.as_bytes(), &mut kek).expect("expand");
+        }
+        "sha512" => {
+            let hk = Hkdf::<Sha512>::new(Some(&salt_bytes), &ikm);
+            hk.expand(kat.hkdf.info.as_bytes(), &mut kek).expect("expand");
+        }
+        other => anyhow::bail!("unsupported hkdf hash '{}'", other),
+    }
     let kek_expected = hex_to::<32>(&kat.hkdf.kek_hex);
-    if kek != kek_expected { anyhow::bail!("KEK mismatch"); }
+    if kek != kek_expected {
+        anyhow::bail!("KEK mismatch");
+    }
 
-    // Wrap check (AES-256-GCM)
+    // Wrap check (AES-256-GCM; vectors whose wrap.alg is anything else are rejected)
     assert_eq!(kat.wrap.alg, "aes256-gcm");
     let nonce_bytes = hex_to::<12>(&kat.wrap.nonce_hex);
     let nonce = N12::from_slice(&nonce_bytes);
@@ -97,15 +190,148 @@ fn main() -> anyhow::Result<()> {
     let wrapped_expected = hex::decode(&kat.wrap.wrapped_hex)?;
     let aead_gcm = Aes256Gcm::new_from_slice(&kek).unwrap();
     let wrapped = aead_gcm.encrypt(nonce, cek.as_slice()).expect("wrap");
-    if wrapped != wrapped_expected { anyhow::bail!("Wrapped CEK mismatch"); }
+    if wrapped != wrapped_expected {
+        anyhow::bail!("Wrapped CEK mismatch");
+    }
 
-    // Optionally verify SIV chunk if k1 published (not in this KAT)
-    // let k1 = hex_to::<32>(&kat.stream_k1_hex);
-    // let nonce0 = { let mut n=[0u8;12]; n[..8].copy_from_slice(&hex::decode(&kat.file_id_hex)?); n[8..].copy_from_slice(&0u32.to_be_bytes()); N12::from_slice(&n) };
-    // let aead_siv = Aes256GcmSiv::new_from_slice(&k1).unwrap();
-    // let got = aead_siv.encrypt(nonce0, aead::Payload{ msg: kat.chunk0_siv.pt_utf8.as_bytes(), aad: &aad }).unwrap();
-    // assert_eq!(got, hex::decode(&kat.chunk0_siv.ct_hex)?, "SIV chunk ct mismatch");
+    // Chunk-0 AEAD check, now that stream_k1_hex travels with the vector.
+    if let Some(k1_hex) = &kat.stream_k1_hex {
+        let suite = Suite::from_str(&kat.suite)?;
+        let k1 = hex_to::<32>(k1_hex);
+        let file_id = hex::decode(&kat.file_id_hex)?;
+        let mut nonce0 = [0u8; 12];
+        nonce0[..8].copy_from_slice(&file_id);
+        nonce0[8..].copy_from_slice(&0u32.to_be_bytes());
+        let got = suite.seal_chunk0(&k1, &nonce0, kat.chunk0_siv.pt_utf8.as_bytes(), &aad);
+        let expected = hex::decode(&kat.chunk0_siv.ct_hex)?;
+        if got != expected {
+            anyhow::bail!("chunk0_siv.ct_hex mismatch");
+        }
+    }
 
     println!("KAT OK");
     Ok(())
 }
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut b = [0u8; N];
+    rand::rngs::OsRng.fill_bytes(&mut b);
+    b
+}
+
+/// Emit a fully-populated, internally-consistent `Kat` for `suite`: the
+/// AAD, HKDF-derived KEK, AES-256-GCM DEK wrap, and the chunk-0 ciphertext
+/// under a fresh stream key — everything `verify` needs to recompute and
+/// cross-check, so a downstream reimplementation can run this generator,
+/// hand its own primitives the same inputs, and diff the outputs.
+fn generate(suite: Suite, chunk_size: u32, file_id_hex: &str, kdf_salt_hex: Option<&str>) -> anyhow::Result<Kat> {
+    let file_id = hex::decode(file_id_hex)?;
+    if file_id.len() != 8 {
+        anyhow::bail!("file_id must be 8 bytes, got {}", file_id.len());
+    }
+
+    let mlkem_ss = random_bytes::<32>();
+    let x25519_ss = random_bytes::<32>();
+    let extract_salt = b"qsfs/v2/salt".to_vec();
+    let salt_bytes = match kdf_salt_hex {
+        Some(s) => hex::decode(s)?,
+        None => extract_salt.clone(),
+    };
+
+    let mut ikm = mlkem_ss.to_vec();
+    ikm.extend_from_slice(&x25519_ss);
+    let info = b"qsfs/kek/v2";
+    let mut kek = [0u8; 32];
+    match suite.hkdf_hash_name() {
+        "sha3-384" => {
+            let hk = Hkdf::<Sha3_384>::new(Some(&salt_bytes), &ikm);
+            hk.expand(info, &mut kek).expect("expand");
+        }
+        "sha512" => {
+            let hk = Hkdf::<Sha512>::new(Some(&salt_bytes), &ikm);
+            hk.expand(info, &mut kek).expect("expand");
+        }
+        _ => unreachable!(),
+    }
+
+    let cek = random_bytes::<32>();
+    let wrap_nonce = random_bytes::<12>();
+    let aead_gcm = Aes256Gcm::new_from_slice(&kek).expect("key");
+    let wrapped = aead_gcm
+        .encrypt(N12::from_slice(&wrap_nonce), cek.as_slice())
+        .expect("wrap");
+
+    let kat_suite = suite.as_str().to_string();
+    let aad = rebuild_aad(&kat_suite, chunk_size, file_id_hex, kdf_salt_hex)?;
+
+    let k1 = random_bytes::<32>();
+    let mut nonce0 = [0u8; 12];
+    nonce0[..8].copy_from_slice(&file_id);
+    nonce0[8..].copy_from_slice(&0u32.to_be_bytes());
+    let pt = b"hello qsfs v2\n";
+    let ct = suite.seal_chunk0(&k1, &nonce0, pt, &aad);
+
+    Ok(Kat {
+        version: "2".to_string(),
+        suite: kat_suite,
+        chunk_size,
+        file_id_hex: file_id_hex.to_string(),
+        kdf_salt_hex: kdf_salt_hex.map(|s| s.to_string()),
+        aad_hex: hex::encode(&aad),
+        hkdf: HkdfKat {
+            hash: suite.hkdf_hash_name().to_string(),
+            extract_salt: String::from_utf8(extract_salt).expect("ascii"),
+            info: String::from_utf8(info.to_vec()).expect("ascii"),
+            mlkem_ss_hex: hex::encode(mlkem_ss),
+            x25519_ss_hex: hex::encode(x25519_ss),
+            kek_hex: hex::encode(kek),
+        },
+        wrap: WrapKat {
+            alg: "aes256-gcm".to_string(),
+            nonce_hex: hex::encode(wrap_nonce),
+            cek_hex: hex::encode(cek),
+            wrapped_hex: hex::encode(&wrapped),
+        },
+        chunk0_siv: ChunkSivKat {
+            pt_utf8: String::from_utf8(pt.to_vec()).expect("ascii"),
+            ct_hex: hex::encode(&ct),
+        },
+        stream_k1_hex: Some(hex::encode(k1)),
+    })
+}
+
+const ALL_SUITES: [Suite; 3] = [
+    Suite::Aes256GcmSiv,
+    Suite::Aes256Gcm,
+    Suite::MlKem1024X25519HkdfSha512ChaCha20Poly1305,
+];
+
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+
+    match args.get(1).map(String::as_str) {
+        Some("generate") => {
+            let chunk_size: u32 = args.get(3).expect("usage: verify-kat generate <suite|all> <chunk_size> <file_id_hex> [kdf_salt_hex]").parse()?;
+            let file_id_hex = args.get(4).expect("missing file_id_hex").clone();
+            let kdf_salt_hex = args.get(5).cloned();
+
+            if args[2] == "all" {
+                let mut kats = Vec::new();
+                for suite in ALL_SUITES {
+                    kats.push(generate(suite, chunk_size, &file_id_hex, kdf_salt_hex.as_deref())?);
+                }
+                println!("{}", serde_json::to_string_pretty(&kats)?);
+            } else {
+                let suite = Suite::from_str(&args[2])?;
+                let kat = generate(suite, chunk_size, &file_id_hex, kdf_salt_hex.as_deref())?;
+                println!("{}", serde_json::to_string_pretty(&kat)?);
+            }
+        }
+        Some(path) => verify(path)?,
+        None => anyhow::bail!(
+            "usage: verify-kat <file.json> | verify-kat generate <suite|all> <chunk_size> <file_id_hex> [kdf_salt_hex]"
+        ),
+    }
+
+    Ok(())
+}